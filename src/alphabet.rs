@@ -0,0 +1,81 @@
+//! `--alphabet`: the Base58 alphabet B58UUIDs are read and written in, for
+//! systems that use Ripple's or Flickr's digit order instead of Bitcoin's.
+//!
+//! The choice is global, like `--error-format`, and these functions stand in
+//! for the library's wherever a command converts between a UUID and its
+//! B58UUID. Commands tied to the Bitcoin order (`trace`, `sortkey`) refuse
+//! any other.
+//!
+//! The alphabets share their characters, so a B58UUID in the wrong one
+//! usually still decodes, to another UUID, and nothing can tell it apart
+//! from a valid value. A value that fails in the selected alphabet (too
+//! large for 128 bits) but decodes in another gets an error naming that
+//! one, preferring an alphabet where it is an RFC 9562 UUID.
+
+use b58uuid_cli::{Alphabet, B58Error};
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use std::sync::atomic::{AtomicU8, Ordering};
+use uuid::Uuid;
+
+static ALPHABET: AtomicU8 = AtomicU8::new(0);
+
+/// Parser for the flag, listing the alphabets in `--help`.
+pub fn parser() -> impl TypedValueParser<Value = Alphabet> {
+    PossibleValuesParser::new(Alphabet::ALL.map(Alphabet::name))
+        .map(|name| Alphabet::from_name(&name).expect("one of the possible values"))
+}
+
+pub fn set(alphabet: Alphabet) {
+    let index = Alphabet::ALL.iter().position(|&a| a == alphabet);
+    ALPHABET.store(index.unwrap_or(0) as u8, Ordering::Relaxed);
+}
+
+pub fn get() -> Alphabet {
+    Alphabet::ALL[ALPHABET.load(Ordering::Relaxed) as usize]
+}
+
+pub fn encode(uuid: Uuid) -> String {
+    b58uuid_cli::encode_with(uuid, get())
+}
+
+pub fn decode(b58: &str) -> Result<Uuid, B58Error> {
+    let expected = get();
+    let decoded = b58uuid_cli::decode_with(b58, expected);
+    if !matches!(
+        decoded,
+        Err(B58Error::Overflow | B58Error::InvalidBase58Character { .. })
+    ) {
+        return decoded;
+    }
+    let others = || {
+        Alphabet::ALL
+            .into_iter()
+            .filter(move |&alphabet| alphabet != expected)
+    };
+    let decodes = |alphabet| b58uuid_cli::decode_with(b58, alphabet);
+    let likely = others()
+        .find(|&alphabet| decodes(alphabet).is_ok_and(standard))
+        .or_else(|| others().find(|&alphabet| decodes(alphabet).is_ok()));
+    match likely {
+        Some(likely) => Err(B58Error::WrongAlphabet { expected, likely }),
+        None => decoded,
+    }
+}
+
+pub fn is_valid_b58uuid(b58: &str) -> bool {
+    b58uuid_cli::is_valid_b58uuid_with(b58, get())
+}
+
+/// Whether `uuid` has the RFC 9562 layout: its variant, and a defined
+/// version.
+fn standard(uuid: Uuid) -> bool {
+    uuid.get_variant() == uuid::Variant::RFC4122 && (1..=8).contains(&uuid.get_version_num())
+}
+
+pub fn encode_uuid(uuid: &str) -> Result<String, B58Error> {
+    b58uuid_cli::encode_uuid_with(uuid, get())
+}
+
+pub fn decode_to_uuid(b58: &str) -> Result<String, B58Error> {
+    decode(b58).map(|uuid| uuid.to_string())
+}
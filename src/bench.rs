@@ -0,0 +1,138 @@
+//! `bench storage`: what a dataset costs as UUID text, raw bytes and
+//! B58UUIDs, measured on the dataset itself.
+//!
+//! Sizes count the values only, without separators. Timings are single
+//! passes over the whole dataset with coarse timers, meant to compare the
+//! representations with each other rather than as absolute benchmarks; run a
+//! release build for meaningful numbers.
+
+use anyhow::{bail, Result};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::input::Lines;
+use crate::output::{OutputWriter, Record};
+
+/// One representation's share of the report.
+struct Measurement {
+    name: &'static str,
+    total_bytes: usize,
+    parse: Duration,
+    sort: Duration,
+}
+
+/// Time `f`, returning its duration.
+fn time(f: impl FnOnce()) -> Duration {
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+fn ms(elapsed: Duration) -> f64 {
+    (elapsed.as_micros() as f64) / 1000.0
+}
+
+/// Human-readable byte count.
+pub fn size(bytes: usize) -> String {
+    match bytes {
+        b if b >= 1 << 20 => format!("{:.1} MiB", b as f64 / (1 << 20) as f64),
+        b if b >= 1 << 10 => format!("{:.1} KiB", b as f64 / (1 << 10) as f64),
+        b => format!("{} B", b),
+    }
+}
+
+/// Measure every ID in `lines` as text, binary and B58UUID, writing one
+/// record per representation. Lines that are not IDs are skipped with a
+/// warning.
+pub fn storage(lines: Lines, out: &mut dyn OutputWriter) -> Result<()> {
+    let mut uuids = Vec::new();
+    let mut skipped = 0;
+    for line in lines {
+        let line = line?;
+        let value = line.trim();
+        if value.is_empty() {
+            continue;
+        }
+        match crate::classify(value).and_then(|(_, uuid, _)| uuid::Uuid::parse_str(&uuid).ok()) {
+            Some(uuid) => uuids.push(uuid),
+            None => skipped += 1,
+        }
+    }
+    if skipped > 0 {
+        eprintln!("Skipped {} lines that are not IDs", skipped);
+    }
+    if uuids.is_empty() {
+        bail!("No IDs to measure");
+    }
+
+    let texts: Vec<String> = uuids.iter().map(|u| u.to_string()).collect();
+    let b58s: Vec<String> = uuids
+        .iter()
+        .map(|u| b58uuid::encode(u.as_bytes()))
+        .collect();
+    let binaries: Vec<[u8; 16]> = uuids.iter().map(|u| *u.as_bytes()).collect();
+
+    let mut measurements = [
+        Measurement {
+            name: "text",
+            total_bytes: texts.iter().map(String::len).sum(),
+            parse: time(|| {
+                for text in &texts {
+                    black_box(uuid::Uuid::parse_str(text).ok());
+                }
+            }),
+            sort: Duration::ZERO,
+        },
+        Measurement {
+            name: "binary",
+            total_bytes: binaries.len() * 16,
+            parse: time(|| {
+                for bytes in &binaries {
+                    black_box(uuid::Uuid::from_bytes(*black_box(bytes)));
+                }
+            }),
+            sort: Duration::ZERO,
+        },
+        Measurement {
+            name: "b58uuid",
+            total_bytes: b58s.iter().map(String::len).sum(),
+            parse: time(|| {
+                for b58 in &b58s {
+                    black_box(b58uuid::decode(b58).ok());
+                }
+            }),
+            sort: Duration::ZERO,
+        },
+    ];
+    // Sorting is dominated by comparisons, so it stands in for compare cost
+    let (mut texts, mut binaries, mut b58s) = (texts, binaries, b58s);
+    measurements[0].sort = time(|| black_box(&mut texts).sort_unstable());
+    measurements[1].sort = time(|| black_box(&mut binaries).sort_unstable());
+    measurements[2].sort = time(|| black_box(&mut b58s).sort_unstable());
+
+    let text_bytes = measurements[0].total_bytes as f64;
+    for m in &measurements {
+        let share = m.total_bytes as f64 / text_bytes * 100.0;
+        let per_id = m.total_bytes as f64 / uuids.len() as f64;
+        let text = format!(
+            "{:<8} {:>10} ({:>5.1} B/id, {:>5.1}% of text)  parse {:>9.3} ms  sort {:>9.3} ms",
+            m.name,
+            size(m.total_bytes),
+            per_id,
+            share,
+            ms(m.parse),
+            ms(m.sort)
+        );
+        out.record(
+            Record::new(text)
+                .field("representation", m.name)
+                .field("ids", uuids.len())
+                .field("total_bytes", m.total_bytes)
+                .field("bytes_per_id", per_id)
+                .field("percent_of_text", (share * 10.0).round() / 10.0)
+                .field("parse_ms", ms(m.parse))
+                .field("sort_ms", ms(m.sort)),
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,49 @@
+//! Byte order between a UUID and the 128-bit integer that is Base58-encoded.
+//!
+//! `network` (the default) reads the 16 bytes in RFC 4122 order, the order
+//! the hex digits appear in the text form, as one big-endian integer. This is
+//! what every B58UUID library does.
+//!
+//! `mixed` first applies the Microsoft GUID layout: the first three fields
+//! (4, 2 and 2 bytes) are stored little-endian, the last 8 bytes unchanged.
+//! So `00112233-4455-6677-8899-aabbccddeeff` is encoded as the integer with
+//! bytes `33 22 11 00 55 44 77 66 88 99 aa bb cc dd ee ff`. The swap is its
+//! own inverse, so decode undoes it the same way.
+
+use clap::ValueEnum;
+use uuid::Uuid;
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ByteOrder {
+    /// RFC 4122 byte order, big-endian (the text order of the hex digits)
+    Network,
+    /// Microsoft GUID order: first three fields little-endian
+    Mixed,
+}
+
+/// Rewrite `uuid` with its bytes in GUID order (or back again): the UUID
+/// whose network-order bytes are `uuid`'s mixed-order bytes. Parsing goes
+/// through the codec so invalid input fails with the usual errors.
+pub fn swap_fields(uuid: &str) -> Result<String, b58uuid_cli::B58Error> {
+    let bytes = b58uuid::decode(&b58uuid_cli::encode_uuid(uuid)?)?;
+    Ok(Uuid::from_bytes_le(bytes).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swaps_the_first_three_fields() {
+        assert_eq!(
+            swap_fields("00112233-4455-6677-8899-aabbccddeeff").unwrap(),
+            "33221100-5544-7766-8899-aabbccddeeff"
+        );
+    }
+
+    #[test]
+    fn swap_is_its_own_inverse() {
+        let uuid = "550e8400-e29b-41d4-a716-446655440000";
+        assert_eq!(swap_fields(&swap_fields(uuid).unwrap()).unwrap(), uuid);
+    }
+}
@@ -0,0 +1,203 @@
+//! Base58Check B58UUIDs: the 16 UUID bytes followed by a 4-byte checksum
+//! (the first bytes of a double SHA-256 of the UUID), Base58-encoded as one
+//! number in the `--alphabet` and padded with its zero digit (`1` in
+//! Bitcoin's) to 28 characters, the most 20 bytes can need.
+//! The fixed length keeps them distinct from plain 22-character B58UUIDs, and
+//! a mistyped character is caught by the checksum.
+
+use b58uuid_cli::B58Error;
+use colored::*;
+use sha2::{Digest, Sha256};
+
+use crate::output::Record;
+
+/// First 4 bytes of SHA-256(SHA-256(`bytes`)).
+fn checksum(bytes: &[u8]) -> [u8; 4] {
+    let digest = Sha256::digest(Sha256::digest(bytes));
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+/// Length of every Base58Check B58UUID.
+const CHECKED_LEN: usize = 28;
+
+/// Base58-encode the 20 `bytes` as one big-endian number, padded to
+/// `CHECKED_LEN` characters.
+fn base58_encode(bytes: &[u8; 20]) -> String {
+    // Little-endian base-58 digits of the number
+    let mut digits = [0u8; CHECKED_LEN];
+    for &byte in bytes {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+    }
+    digits
+        .iter()
+        .rev()
+        .map(|&d| crate::alphabet::get().digits()[d as usize] as char)
+        .collect()
+}
+
+/// Inverse of [`base58_encode`]; `None` unless `value` is `CHECKED_LEN`
+/// Base58 characters whose number fits in 20 bytes.
+fn base58_decode(value: &str) -> Option<[u8; 20]> {
+    if value.len() != CHECKED_LEN {
+        return None;
+    }
+    // Little-endian bytes of the number
+    let mut bytes = [0u8; 20];
+    for ch in value.chars() {
+        let mut carry = crate::alphabet::get()
+            .digits()
+            .iter()
+            .position(|&c| c as char == ch)? as u32;
+        for byte in bytes.iter_mut() {
+            carry += *byte as u32 * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        if carry > 0 {
+            return None;
+        }
+    }
+    bytes.reverse();
+    Some(bytes)
+}
+
+/// Base58Check form of a UUID string.
+pub fn encode_uuid(value: &str) -> Result<String, B58Error> {
+    Ok(encode(crate::encoding::Encoding::Uuid.decode(value)?))
+}
+
+/// Base58Check form of `uuid`.
+pub fn encode(uuid: uuid::Uuid) -> String {
+    let bytes = uuid.as_bytes();
+    let mut payload = [0u8; 20];
+    payload[..16].copy_from_slice(bytes);
+    payload[16..].copy_from_slice(&checksum(bytes));
+    base58_encode(&payload)
+}
+
+/// The UUID of a Base58Check B58UUID whose checksum matches.
+pub fn decode(value: &str) -> Result<uuid::Uuid, B58Error> {
+    let got = value.chars().count();
+    if got != CHECKED_LEN {
+        return Err(B58Error::WrongLength {
+            expected: CHECKED_LEN,
+            got,
+        });
+    }
+    if let Some((index, ch)) = value
+        .chars()
+        .enumerate()
+        .find(|&(_, ch)| !ch.is_ascii() || !crate::alphabet::get().digits().contains(&(ch as u8)))
+    {
+        return Err(B58Error::InvalidBase58Character { ch, index });
+    }
+    let bytes = base58_decode(value).ok_or(B58Error::Overflow)?;
+    let (uuid, sum) = bytes.split_at(16);
+    if checksum(uuid) != sum {
+        return Err(B58Error::InvalidBase58(
+            "Checksum failed; the value was altered or mistyped".to_string(),
+        ));
+    }
+    Ok(uuid::Uuid::from_slice(uuid).expect("16 bytes"))
+}
+
+/// Outcome of checking a value for an embedded checksum.
+enum Checksum {
+    /// The checksum matched; holds the UUID.
+    Passed(uuid::Uuid),
+    /// The value has room for a checksum, but it does not match.
+    Failed,
+    /// A plain UUID or B58UUID, with no checksum at all.
+    Absent,
+}
+
+/// Check `value`, or `None` if it is neither a Base58Check B58UUID nor a
+/// plain UUID/B58UUID.
+fn verify(value: &str) -> Option<Checksum> {
+    if let Some(bytes) = base58_decode(value) {
+        let (uuid, sum) = bytes.split_at(16);
+        return Some(if checksum(uuid) == sum {
+            Checksum::Passed(uuid::Uuid::from_slice(uuid).ok()?)
+        } else {
+            Checksum::Failed
+        });
+    }
+    crate::classify(value).map(|_| Checksum::Absent)
+}
+
+/// Validation record for `--check`, with `text` built by `describe` from the
+/// verdict.
+fn check_record(value: &str, describe: impl Fn(Option<&Checksum>) -> String) -> Record {
+    let verdict = verify(value);
+    let record = Record::new(describe(verdict.as_ref())).field("input", value);
+    match verdict {
+        Some(Checksum::Passed(uuid)) => record
+            .field("valid", true)
+            .field("checksum", "passed")
+            .field("uuid", crate::output::uuid_case(&uuid.to_string())),
+        Some(Checksum::Failed) => record
+            .field("valid", false)
+            .field("checksum", "failed")
+            .field("uuid", serde_json::Value::Null)
+            .failure(),
+        Some(Checksum::Absent) => record
+            .field("valid", false)
+            .field("checksum", "absent")
+            .field("uuid", serde_json::Value::Null)
+            .failure(),
+        None => record
+            .field("valid", false)
+            .field("checksum", serde_json::Value::Null)
+            .field("uuid", serde_json::Value::Null)
+            .failure(),
+    }
+}
+
+/// One status line for a batch `validate --check`.
+pub fn line_record(value: &str) -> Record {
+    check_record(value, |verdict| match verdict {
+        Some(Checksum::Passed(_)) => {
+            format!("{} {} (checksum passed)", "✓".green().bold(), value)
+        }
+        Some(Checksum::Failed) => format!("{} {} - checksum failed", "✗".red().bold(), value),
+        Some(Checksum::Absent) => format!("{} {} - missing checksum", "✗".red().bold(), value),
+        None => format!("{} {} - Invalid format", "✗".red().bold(), value),
+    })
+}
+
+/// What `validate --check` wants, for the reports of values that are not it.
+const EXPECTED: &str = "Expected: Base58Check B58UUID (28 chars)";
+
+/// The detailed report for a single `validate --check` value.
+pub fn value_record(value: &str) -> Record {
+    check_record(value, |verdict| match verdict {
+        Some(Checksum::Passed(uuid)) => format!(
+            "{} Valid Base58Check B58UUID (checksum passed)\n  Value: {}\n  UUID:  {}",
+            "✓".green().bold(),
+            value.cyan(),
+            crate::output::uuid_case(&uuid.to_string()).cyan()
+        ),
+        Some(Checksum::Failed) => format!(
+            "{} Checksum failed\n  Value: {}\n  The value was altered or mistyped",
+            "✗".red().bold(),
+            value
+        ),
+        Some(Checksum::Absent) => format!(
+            "{} Missing checksum\n  Value: {}\n  {}; encode with --check",
+            "✗".red().bold(),
+            value,
+            EXPECTED
+        ),
+        None => format!(
+            "{} Invalid format\n  Value: {}\n  {}",
+            "✗".red().bold(),
+            value,
+            EXPECTED
+        ),
+    })
+}
@@ -0,0 +1,60 @@
+//! `count`: how often each distinct ID occurs, for finding hot IDs in access
+//! logs and other large files.
+//!
+//! IDs are picked out anywhere in a line, as `url-rewrite` finds them:
+//! hyphenated or bare-hex UUIDs (braced and URN forms included) and
+//! 22-character B58UUIDs. Each is reduced to its 16 bytes, so the UUID and
+//! B58UUID forms of one ID are counted together.
+
+use anyhow::Result;
+use colored::*;
+use regex::Regex;
+use std::collections::HashMap;
+
+use crate::input::Lines;
+use crate::output::{OutputWriter, Record};
+use crate::rewrite::{B58UUID_PATTERN, UUID_PATTERN};
+
+/// 32 hex digits standing alone, the bare form of a UUID.
+const HEX_UUID_PATTERN: &str = r"\b[0-9a-fA-F]{32}\b";
+
+/// Write one `<count> <b58uuid>` record per distinct ID in `lines`, most
+/// frequent first and ties in B58UUID order, so the report is deterministic.
+/// With `stats`, the totals go to stderr.
+pub fn count(lines: Lines, stats: bool, out: &mut dyn OutputWriter) -> Result<()> {
+    let pattern = Regex::new(&format!(
+        "{}|{}|{}",
+        UUID_PATTERN, HEX_UUID_PATTERN, B58UUID_PATTERN
+    ))?;
+    let mut counts: HashMap<uuid::Uuid, usize> = HashMap::new();
+    let mut total = 0;
+    for line in lines {
+        let line = line?;
+        for token in pattern.find_iter(&line) {
+            let uuid = crate::classify(token.as_str())
+                .and_then(|(_, uuid, _)| uuid::Uuid::parse_str(&uuid).ok());
+            if let Some(uuid) = uuid {
+                *counts.entry(uuid).or_default() += 1;
+                total += 1;
+            }
+        }
+    }
+
+    let mut counts: Vec<(usize, String, uuid::Uuid)> = counts
+        .into_iter()
+        .map(|(uuid, n)| (n, crate::alphabet::encode(uuid), uuid))
+        .collect();
+    counts.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    if stats {
+        eprintln!("Counted {} IDs, {} distinct", total, counts.len());
+    }
+    for (n, b58, uuid) in counts {
+        out.record(
+            Record::new(format!("{} {}", n, b58.green()))
+                .field("count", n)
+                .field("b58uuid", b58)
+                .field("uuid", uuid.to_string()),
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,136 @@
+//! `--unique` and `--dedupe-window`: drop results repeating one already
+//! written, keeping the first of each in input order.
+//!
+//! `--unique` remembers every distinct result, so its memory grows with the
+//! number of distinct values (roughly 100 bytes each; a few GB for tens of
+//! millions). `--dedupe-window` bounds it instead:
+//! Only the last `N` results written are remembered (a ring buffer plus a set
+//! over it), so memory stays fixed however long the stream runs. A repeat is
+//! caught only if no more than `N - 1` other results were written since its
+//! first copy; duplicates further apart pass through. Either way results are
+//! matched on their converted value, so inputs written differently (upper
+//! case, no hyphens, braces) that stand for the same UUID count as one.
+
+use anyhow::Result;
+use std::collections::{HashSet, VecDeque};
+
+use crate::output::{OutputWriter, Record};
+use crate::sort::key;
+
+pub struct UniqueWriter<'a> {
+    inner: Box<dyn OutputWriter + 'a>,
+    seen: HashSet<String>,
+}
+
+impl<'a> UniqueWriter<'a> {
+    pub fn new(inner: Box<dyn OutputWriter + 'a>) -> Self {
+        UniqueWriter {
+            inner,
+            seen: HashSet::new(),
+        }
+    }
+}
+
+impl OutputWriter for UniqueWriter<'_> {
+    fn record(&mut self, record: Record) -> Result<()> {
+        if !self.seen.insert(key(&record)) {
+            return Ok(());
+        }
+        self.inner.record(record)
+    }
+
+    fn error(&mut self, input: &str, message: &str) -> Result<()> {
+        self.inner.error(input, message)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.inner.finish()
+    }
+}
+
+pub struct DedupeWindowWriter<'a> {
+    inner: Box<dyn OutputWriter + 'a>,
+    size: usize,
+    /// Keys of the last `size` records written, oldest first.
+    window: VecDeque<String>,
+    in_window: HashSet<String>,
+}
+
+impl<'a> DedupeWindowWriter<'a> {
+    pub fn new(inner: Box<dyn OutputWriter + 'a>, size: usize) -> Self {
+        DedupeWindowWriter {
+            inner,
+            size,
+            window: VecDeque::with_capacity(size),
+            in_window: HashSet::with_capacity(size),
+        }
+    }
+}
+
+impl OutputWriter for DedupeWindowWriter<'_> {
+    fn record(&mut self, record: Record) -> Result<()> {
+        let key = key(&record);
+        if self.in_window.contains(&key) {
+            return Ok(());
+        }
+        if self.window.len() == self.size {
+            if let Some(oldest) = self.window.pop_front() {
+                self.in_window.remove(&oldest);
+            }
+        }
+        self.in_window.insert(key.clone());
+        self.window.push_back(key);
+        self.inner.record(record)
+    }
+
+    fn error(&mut self, input: &str, message: &str) -> Result<()> {
+        self.inner.error(input, message)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.inner.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::testing::{pair, Collect, PAIRS};
+
+    /// Inputs of the `(output, input)` pairs that pass the writer `wrap`
+    /// builds around a collector.
+    fn kept<'a>(
+        pairs: &[(&str, &str)],
+        wrap: impl FnOnce(Box<dyn OutputWriter + 'a>) -> Box<dyn OutputWriter + 'a>,
+    ) -> Vec<String> {
+        let kept = Collect::default();
+        let mut writer = wrap(Box::new(kept.clone()));
+        for (output, input) in pairs {
+            writer.record(pair(output, input)).unwrap();
+        }
+        writer.finish().unwrap();
+        kept.inputs()
+    }
+
+    #[test]
+    fn unique_keeps_the_first_of_each_in_input_order() {
+        let kept = kept(&PAIRS, |inner| Box::new(UniqueWriter::new(inner)));
+        assert_eq!(kept, ["1", "2", "3"]);
+    }
+
+    #[test]
+    fn window_forgets_results_that_fall_out_of_it() {
+        // With room for two, "b" pushes out "c" so "c" at 5 is written
+        // again, and that pushes out the "a" that caught "a" at 4.
+        let kept = kept(&PAIRS, |inner| Box::new(DedupeWindowWriter::new(inner, 2)));
+        assert_eq!(kept, ["1", "2", "3", "5", "6"]);
+    }
+}
@@ -0,0 +1,175 @@
+//! Version 8 ("custom") UUIDs: deterministic IDs derived from arbitrary input
+//! by hashing, and IDs framed around user-supplied bytes.
+//!
+//! For `derive`, the first 16 bytes of the digest become a version 8 ("custom") UUID as
+//! defined by RFC 9562: the version nibble is set to `8` and the variant bits
+//! to `10`, leaving the remaining 122 bits from the hash. The same input and
+//! hash always give the same ID.
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::ValueEnum;
+use colored::*;
+use sha2::{Digest, Sha256};
+
+use crate::output::{OutputWriter, Record};
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Hash {
+    /// SHA-256
+    Sha256,
+    /// BLAKE3
+    Blake3,
+}
+
+impl Hash {
+    fn name(self) -> &'static str {
+        match self {
+            Hash::Sha256 => "sha256",
+            Hash::Blake3 => "blake3",
+        }
+    }
+
+    /// First 16 bytes of the digest of `input`.
+    fn digest16(self, input: &[u8]) -> [u8; 16] {
+        let mut bytes = [0u8; 16];
+        match self {
+            Hash::Sha256 => bytes.copy_from_slice(&Sha256::digest(input)[..16]),
+            Hash::Blake3 => bytes.copy_from_slice(&blake3::hash(input).as_bytes()[..16]),
+        }
+        bytes
+    }
+}
+
+/// The v8 UUID derived from `input` with `hash`.
+pub fn derive_uuid(input: &str, hash: Hash) -> uuid::Uuid {
+    uuid::Uuid::new_v8(hash.digest16(input.as_bytes()))
+}
+
+pub fn derive(input: &str, hash: Hash, out: &mut dyn OutputWriter) -> Result<()> {
+    let uuid = derive_uuid(input, hash);
+    let b58 = crate::alphabet::encode(uuid);
+    out.record(
+        Record::new(b58.green().to_string())
+            .field("input", input)
+            .field("hash", hash.name())
+            .field("uuid", uuid.to_string())
+            .field("b58uuid", b58),
+    )
+}
+
+/// Parse exactly 16 bytes of hex, ignoring whitespace and an optional
+/// `0x`/`0X` prefix.
+pub(crate) fn parse_hex16(input: &str) -> Result<[u8; 16]> {
+    let digits: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+    let digits = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+        .unwrap_or(&digits);
+    if let Some(bad) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+        bail!("Invalid hex digit '{}'", bad);
+    }
+    if digits.len() != 32 {
+        bail!(
+            "Invalid length: expected 16 bytes (32 hex digits), got {} hex digits",
+            digits.len()
+        );
+    }
+    let mut bytes = [0u8; 16];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&digits[i * 2..i * 2 + 2], 16)?;
+    }
+    Ok(bytes)
+}
+
+/// Read exactly 16 raw bytes from `path`.
+fn read_bytes16(path: &str) -> Result<[u8; 16]> {
+    let data = std::fs::read(path).context(format!("Failed to read file: {}", path))?;
+    data.as_slice().try_into().map_err(|_| {
+        anyhow!(
+            "Invalid length: expected 16 bytes, got {} in {}",
+            data.len(),
+            path
+        )
+    })
+}
+
+/// Frame 16 bytes of user data (hex, or raw bytes from `file`) as a v8 UUID
+/// and print its B58UUID. The version and variant bits of the data are
+/// overwritten.
+pub fn v8(hex: Option<&str>, file: Option<&str>, out: &mut dyn OutputWriter) -> Result<()> {
+    let bytes = match (hex, file) {
+        (Some(hex), _) => parse_hex16(hex),
+        (None, Some(path)) => read_bytes16(path),
+        (None, None) => Err(anyhow!("Provide 16 bytes as hex or with --file")),
+    };
+    let bytes = match bytes {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+    let uuid = uuid::Uuid::new_v8(bytes);
+    let b58 = crate::alphabet::encode(uuid);
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    out.record(
+        Record::new(b58.green().to_string())
+            .field("data", hex)
+            .field("uuid", uuid.to_string())
+            .field("b58uuid", b58),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_vectors() {
+        // SHA-256("abc") = ba7816bf8f01cfea414140de5dae2223..., version and
+        // variant bits overwritten
+        assert_eq!(
+            derive_uuid("abc", Hash::Sha256).to_string(),
+            "ba7816bf-8f01-8fea-8141-40de5dae2223"
+        );
+        assert_eq!(
+            derive_uuid("", Hash::Sha256).to_string(),
+            "e3b0c442-98fc-8c14-9afb-f4c8996fb924"
+        );
+    }
+
+    #[test]
+    fn blake3_vectors() {
+        // BLAKE3("abc") = 6437b3ac38465133ffb63b75273a8db5...
+        assert_eq!(
+            derive_uuid("abc", Hash::Blake3).to_string(),
+            "6437b3ac-3846-8133-bfb6-3b75273a8db5"
+        );
+        assert_eq!(
+            derive_uuid("", Hash::Blake3).to_string(),
+            "af1349b9-f5f9-81a6-a040-4dea36dcc949"
+        );
+    }
+
+    #[test]
+    fn derived_ids_are_v8() {
+        for hash in [Hash::Sha256, Hash::Blake3] {
+            let uuid = derive_uuid("user@example.com", hash);
+            assert_eq!(uuid.get_version_num(), 8);
+            assert_eq!(uuid.get_variant(), uuid::Variant::RFC4122);
+        }
+    }
+
+    #[test]
+    fn hex_data_is_exactly_16_bytes() {
+        assert_eq!(
+            parse_hex16("0x00112233 44556677 8899aabb ccddeeff").unwrap(),
+            [
+                0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd,
+                0xee, 0xff
+            ]
+        );
+        assert!(parse_hex16("0011").is_err());
+        assert!(parse_hex16("zz112233445566778899aabbccddeeff").is_err());
+    }
+}
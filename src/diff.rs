@@ -0,0 +1,68 @@
+//! `diff`: whether two identifiers, each a UUID or a B58UUID in any form the
+//! other commands read, stand for the same UUID.
+
+use anyhow::Result;
+use colored::*;
+use uuid::Uuid;
+
+use crate::output::{OutputWriter, Record};
+
+/// How the two values compare; the exit code of `diff` by its number.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Same = 0,
+    Different = 1,
+    Invalid = 2,
+}
+
+/// The UUID `value` stands for, or why it is neither a UUID nor a B58UUID.
+fn normalize(value: &str) -> Result<Uuid, String> {
+    crate::alphabet::decode(value)
+        .or_else(|_| {
+            crate::alphabet::encode_uuid(value).and_then(|b58| crate::alphabet::decode(&b58))
+        })
+        .map_err(|_| crate::invalid_reason(value))
+}
+
+/// Compare `a` and `b`, writing one record, or an error for each value that
+/// is invalid.
+pub fn diff(a: &str, b: &str, out: &mut dyn OutputWriter) -> Result<Outcome> {
+    let (a, b) = (a.trim(), b.trim());
+    let (a_uuid, b_uuid) = match (normalize(a), normalize(b)) {
+        (Ok(a_uuid), Ok(b_uuid)) => (a_uuid, b_uuid),
+        (a_result, b_result) => {
+            for (value, result) in [(a, a_result), (b, b_result)] {
+                if let Err(reason) = result {
+                    out.error(value, &reason)?;
+                }
+            }
+            return Ok(Outcome::Invalid);
+        }
+    };
+    let same = a_uuid == b_uuid;
+    let text = if same {
+        format!("{} {}", "SAME".green().bold(), a_uuid)
+    } else {
+        format!(
+            "{} {} is {}, {} is {}",
+            "DIFFERENT".red().bold(),
+            a,
+            a_uuid,
+            b,
+            b_uuid
+        )
+    };
+    out.record(
+        Record::new(text)
+            .field("a", a)
+            .field("b", b)
+            .field("a_uuid", a_uuid.to_string())
+            .field("b_uuid", b_uuid.to_string())
+            .field("same", same),
+    )?;
+    Ok(if same {
+        Outcome::Same
+    } else {
+        Outcome::Different
+    })
+}
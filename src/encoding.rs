@@ -0,0 +1,248 @@
+//! `transcode --input-encoding/--output-encoding`: convert directly between
+//! the text encodings of a UUID's 16 bytes, so the tool works as a general
+//! compact-ID converter and not only between UUIDs and B58UUIDs.
+//!
+//! Every value is decoded to its 16 bytes in the input encoding and those
+//! bytes re-encoded in the output one; nothing goes through the hyphenated
+//! form unless that is one of the two encodings.
+
+use b58uuid_cli::B58Error;
+use clap::ValueEnum;
+use uuid::Uuid;
+
+use crate::check;
+
+/// Base62 alphabet in ASCII order (digits, upper case, lower case), as used
+/// by KSUID and most Base62 ID libraries.
+const BASE62_ALPHABET: &[u8; 62] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Characters of every Base62 UUID: 62^22 is the first power above 2^128.
+const BASE62_LEN: usize = 22;
+
+/// Crockford's Base32 alphabet: digits and upper-case letters without I, L,
+/// O and U.
+const BASE32_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Characters of every Base32 UUID: 26 five-bit digits hold 130 bits.
+const BASE32_LEN: usize = 26;
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Encoding {
+    /// Hyphenated UUID (hyphens optional on input)
+    Uuid,
+    /// 32 lowercase hex digits, no hyphens
+    Hex,
+    /// B58UUID (22 characters)
+    Base58,
+    /// Base58Check B58UUID (28 characters, with a checksum)
+    Base58check,
+    /// Base62, digits then upper then lower case, padded with '0' to 22
+    /// characters
+    Base62,
+    /// Crockford Base32, upper case, padded with '0' to 26 characters;
+    /// case-insensitive on input
+    Base32,
+}
+
+impl Encoding {
+    /// Name of the encoding in the `format` field.
+    pub fn name(self) -> &'static str {
+        match self {
+            Encoding::Uuid => "uuid",
+            Encoding::Hex => "hex",
+            Encoding::Base58 => "b58uuid",
+            Encoding::Base58check => "b58uuid-check",
+            Encoding::Base62 => "base62",
+            Encoding::Base32 => "base32",
+        }
+    }
+
+    pub fn decode(self, value: &str) -> Result<Uuid, B58Error> {
+        match self {
+            Encoding::Uuid | Encoding::Hex => {
+                b58uuid_cli::decode(&b58uuid_cli::encode_uuid(value)?)
+            }
+            Encoding::Base58 => crate::alphabet::decode(value),
+            Encoding::Base58check => check::decode(value),
+            Encoding::Base62 => base62_decode(value),
+            Encoding::Base32 => base32_decode(value),
+        }
+    }
+
+    pub fn encode(self, uuid: Uuid) -> String {
+        match self {
+            Encoding::Uuid => uuid.to_string(),
+            Encoding::Hex => uuid.simple().to_string(),
+            Encoding::Base58 => crate::alphabet::encode(uuid),
+            Encoding::Base58check => check::encode(uuid),
+            Encoding::Base62 => base62_encode(uuid),
+            Encoding::Base32 => base32_encode(uuid),
+        }
+    }
+}
+
+/// Decode `value` from `from`, or with `from` unset from whichever of UUID
+/// and B58UUID it is, and encode it in `to`.
+pub fn recode(value: &str, from: Option<Encoding>, to: Encoding) -> Result<String, B58Error> {
+    let uuid = match from {
+        Some(from) => from.decode(value)?,
+        None => match crate::alphabet::decode(value) {
+            Ok(uuid) => uuid,
+            Err(decode_error) => Encoding::Uuid.decode(value).map_err(|encode_error| {
+                if crate::looks_like_uuid(value) {
+                    encode_error
+                } else {
+                    decode_error
+                }
+            })?,
+        },
+    };
+    Ok(to.encode(uuid))
+}
+
+fn base62_encode(uuid: Uuid) -> String {
+    let mut value = uuid.as_u128();
+    let mut digits = [b'0'; BASE62_LEN];
+    for digit in digits.iter_mut().rev() {
+        *digit = BASE62_ALPHABET[(value % 62) as usize];
+        value /= 62;
+    }
+    String::from_utf8(digits.to_vec()).expect("alphabet is ASCII")
+}
+
+fn base62_decode(value: &str) -> Result<Uuid, B58Error> {
+    let invalid = |message: String| B58Error::InvalidEncoding {
+        encoding: "Base62",
+        message,
+    };
+    if value.is_empty() {
+        return Err(invalid("Empty Base62 string".to_string()));
+    }
+    let mut total = 0u128;
+    for (index, ch) in value.chars().enumerate() {
+        let digit = BASE62_ALPHABET
+            .iter()
+            .position(|&c| c as char == ch)
+            .ok_or_else(|| invalid(format!("Invalid character at position {}: {}", index, ch)))?;
+        total = total
+            .checked_mul(62)
+            .and_then(|total| total.checked_add(digit as u128))
+            .ok_or(B58Error::Overflow)?;
+    }
+    Ok(Uuid::from_u128(total))
+}
+
+fn base32_encode(uuid: Uuid) -> String {
+    let mut value = uuid.as_u128();
+    let mut digits = [b'0'; BASE32_LEN];
+    for digit in digits.iter_mut().rev() {
+        *digit = BASE32_ALPHABET[(value % 32) as usize];
+        value /= 32;
+    }
+    String::from_utf8(digits.to_vec()).expect("alphabet is ASCII")
+}
+
+/// The value of a Crockford digit in either case, reading the look-alikes
+/// `I` and `L` as 1 and `O` as 0 as the spec asks.
+fn base32_digit(ch: char) -> Option<u128> {
+    let ch = match ch.to_ascii_uppercase() {
+        'I' | 'L' => '1',
+        'O' => '0',
+        ch => ch,
+    };
+    BASE32_ALPHABET
+        .iter()
+        .position(|&c| c as char == ch)
+        .map(|digit| digit as u128)
+}
+
+/// Crockford Base32 in any case; hyphens, which the spec allows as
+/// separators for readability, are skipped.
+fn base32_decode(value: &str) -> Result<Uuid, B58Error> {
+    let invalid = |message: String| B58Error::InvalidEncoding {
+        encoding: "Base32",
+        message,
+    };
+    if value.is_empty() {
+        return Err(invalid("Empty Base32 string".to_string()));
+    }
+    let mut total = 0u128;
+    for (index, ch) in value.chars().enumerate().filter(|&(_, ch)| ch != '-') {
+        let digit = base32_digit(ch)
+            .ok_or_else(|| invalid(format!("Invalid character at position {}: {}", index, ch)))?;
+        total = total
+            .checked_mul(32)
+            .and_then(|total| total.checked_add(digit))
+            .ok_or(B58Error::Overflow)?;
+    }
+    Ok(Uuid::from_u128(total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UUIDS: [&str; 5] = [
+        "00000000-0000-0000-0000-000000000000",
+        "550e8400-e29b-41d4-a716-446655440000",
+        "6ba7b810-9dad-11d1-80b4-00c04fd430c8",
+        "00ff0000-0000-0000-0000-000000000001",
+        "ffffffff-ffff-ffff-ffff-ffffffffffff",
+    ];
+
+    #[test]
+    fn base32_and_base58_round_trip_the_same_uuids() {
+        for uuid in UUIDS {
+            let uuid = Uuid::parse_str(uuid).unwrap();
+            for encoding in [Encoding::Base32, Encoding::Base58] {
+                let encoded = encoding.encode(uuid);
+                assert_eq!(encoding.decode(&encoded), Ok(uuid), "{}", encoded);
+            }
+            assert_eq!(Encoding::Base32.encode(uuid).len(), 26);
+        }
+        let max = Uuid::from_u128(u128::MAX);
+        assert_eq!(Encoding::Base32.encode(max), format!("7{}", "Z".repeat(25)));
+    }
+
+    #[test]
+    fn base32_reads_any_case_aliases_and_hyphens() {
+        let uuid = Uuid::parse_str(UUIDS[1]).unwrap();
+        let encoded = Encoding::Base32.encode(uuid);
+        let decode = |value: &str| Encoding::Base32.decode(value);
+        assert_eq!(decode(&encoded.to_lowercase()), Ok(uuid));
+        assert_eq!(
+            decode(&format!("{}-{}", &encoded[..13], &encoded[13..])),
+            Ok(uuid)
+        );
+
+        let one = Uuid::from_u128(1);
+        assert_eq!(decode(&format!("{}1", "0".repeat(25))), Ok(one));
+        assert_eq!(decode(&format!("{}I", "O".repeat(25))), Ok(one));
+        assert_eq!(decode(&format!("{}l", "o".repeat(25))), Ok(one));
+    }
+
+    #[test]
+    fn base32_rejects_values_above_128_bits() {
+        // The first of 26 digits carries only the top three bits.
+        assert_eq!(
+            Encoding::Base32.decode(&format!("8{}", "0".repeat(25))),
+            Err(B58Error::Overflow)
+        );
+        assert_eq!(
+            Encoding::Base32.decode(&"Z".repeat(26)),
+            Err(B58Error::Overflow)
+        );
+        assert_eq!(
+            Encoding::Base32.decode(&format!("1{}", "0".repeat(26))),
+            Err(B58Error::Overflow)
+        );
+        assert!(matches!(
+            Encoding::Base32.decode("0U"),
+            Err(B58Error::InvalidEncoding {
+                encoding: "Base32",
+                ..
+            })
+        ));
+    }
+}
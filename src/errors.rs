@@ -0,0 +1,149 @@
+//! Stable error codes for `--error-format code`.
+//!
+//! Human messages quote the offending value and may be reworded between
+//! releases. A code names only the kind of failure, plus a position or length
+//! where one applies (`E_INVALID_CHAR:0`), so tests and log alerts can match
+//! on it exactly.
+
+use b58uuid_cli::B58Error;
+use clap::ValueEnum;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ErrorFormat {
+    /// Descriptive messages including the offending value
+    Human,
+    /// Stable codes such as `E_INVALID_CHAR:0`, without the value
+    Code,
+}
+
+static CODES: AtomicBool = AtomicBool::new(false);
+
+/// Select the error format for the rest of the run.
+pub fn set_format(format: ErrorFormat) {
+    CODES.store(format == ErrorFormat::Code, Ordering::Relaxed);
+}
+
+/// Whether failures are reported as codes.
+pub fn codes() -> bool {
+    CODES.load(Ordering::Relaxed)
+}
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// `--quiet`: drop the per-value errors of batch runs instead of printing
+/// them to stderr.
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// `code` under `--error-format code`, otherwise `message`.
+pub fn render(code: &str, message: impl Into<String>) -> String {
+    if codes() {
+        code.to_string()
+    } else {
+        message.into()
+    }
+}
+
+/// Stable code for a conversion error, from its variant alone.
+pub fn code(error: &B58Error) -> String {
+    use B58Error::*;
+    match error {
+        InvalidBase58Character { index, .. } => format!("E_INVALID_CHAR:{}", index),
+        NonAsciiBase58 { index, .. } => format!("E_NON_ASCII:{}", index),
+        EmptyBase58 => "E_EMPTY".to_string(),
+        TooManyLeadingOnes => "E_LEADING_ONES".to_string(),
+        InvalidBase58(_) => "E_INVALID_BASE58".to_string(),
+        WrongAlphabet { .. } => "E_WRONG_ALPHABET".to_string(),
+        InvalidUuidFormat(_) => "E_INVALID_UUID".to_string(),
+        InvalidHex { index } => format!("E_INVALID_HEX:{}", index),
+        NonAsciiUuid { index, .. } => format!("E_NON_ASCII:{}", index),
+        MisplacedHyphens => "E_MISPLACED_HYPHENS".to_string(),
+        WrongLength { got, .. } => format!("E_INVALID_LENGTH:{}", got),
+        Overflow => "E_OVERFLOW".to_string(),
+        InvalidEncoding { .. } => "E_INVALID_ENCODING".to_string(),
+    }
+}
+
+/// The flag that gets past a conversion error, for its human message.
+fn hint(error: &B58Error) -> Option<String> {
+    match error {
+        B58Error::NonAsciiUuid { .. } => {
+            Some("--unicode-normalize folds fullwidth characters".to_string())
+        }
+        B58Error::MisplacedHyphens => Some("--tolerant-hyphens ignores them".to_string()),
+        B58Error::WrongAlphabet { likely, .. } => Some(format!("try --alphabet {}", likely.name())),
+        _ => None,
+    }
+}
+
+/// How a codec error is reported under the current error format.
+pub fn describe(error: &B58Error) -> String {
+    let message = match hint(error) {
+        Some(hint) => format!("{} ({})", error, hint),
+        None => error.to_string(),
+    };
+    render(&code(error), message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_depend_on_the_variant_only() {
+        use B58Error::*;
+        let cases = [
+            (
+                InvalidBase58Character { ch: '0', index: 3 },
+                "E_INVALID_CHAR:3",
+            ),
+            (NonAsciiBase58 { ch: 'е', index: 1 }, "E_NON_ASCII:1"),
+            (
+                NonAsciiUuid {
+                    ch: '５', index: 0
+                },
+                "E_NON_ASCII:0",
+            ),
+            (EmptyBase58, "E_EMPTY"),
+            (TooManyLeadingOnes, "E_LEADING_ONES"),
+            (InvalidHex { index: 30 }, "E_INVALID_HEX:30"),
+            (MisplacedHyphens, "E_MISPLACED_HYPHENS"),
+            (
+                WrongLength {
+                    expected: 32,
+                    got: 8,
+                },
+                "E_INVALID_LENGTH:8",
+            ),
+            (Overflow, "E_OVERFLOW"),
+        ];
+        for (error, expected) in cases {
+            assert_eq!(code(&error), expected);
+        }
+        // Free-form messages never leak into the code, whatever they say
+        for message in ["non-ASCII character at position 4", "Misplaced hyphens"] {
+            assert_eq!(
+                code(&InvalidUuidFormat(message.to_string())),
+                "E_INVALID_UUID"
+            );
+            assert_eq!(
+                code(&InvalidBase58(message.to_string())),
+                "E_INVALID_BASE58"
+            );
+        }
+    }
+
+    #[test]
+    fn human_messages_carry_the_hint() {
+        assert_eq!(
+            describe(&B58Error::MisplacedHyphens),
+            "Invalid UUID: Misplaced hyphens: expected 8-4-4-4-12 or none (--tolerant-hyphens ignores them)"
+        );
+    }
+}
@@ -0,0 +1,58 @@
+//! Order- and format-independent hash of a set of IDs, for change detection.
+//!
+//! Every value (UUID in any accepted form, or B58UUID) is reduced to its 16
+//! bytes; the distinct values are sorted and the fingerprint is the BLAKE3
+//! hash of their concatenation. Reordering the file, switching between UUID
+//! and B58UUID forms, or repeating an ID leaves the fingerprint unchanged.
+
+use anyhow::Result;
+use colored::*;
+use std::collections::BTreeSet;
+
+use crate::errors::render;
+use crate::input::Lines;
+use crate::output::{OutputWriter, Record};
+
+/// Write the fingerprint of the IDs in `lines`. Values that are not IDs are
+/// reported and no fingerprint is written, since it would not describe the
+/// file; returns whether one was.
+pub fn fingerprint(lines: Lines, out: &mut dyn OutputWriter) -> Result<bool> {
+    let mut ids = BTreeSet::new();
+    let mut invalid = 0;
+    for (index, line) in lines.enumerate() {
+        let line = line?;
+        let value = line.trim();
+        if value.is_empty() {
+            continue;
+        }
+        match crate::classify(value).and_then(|(_, uuid, _)| uuid::Uuid::parse_str(&uuid).ok()) {
+            Some(uuid) => {
+                ids.insert(*uuid.as_bytes());
+            }
+            None => {
+                invalid += 1;
+                out.error(
+                    &format!("line {}: {}", index + 1, value),
+                    &render("E_INVALID_FORMAT", "not a UUID or B58UUID"),
+                )?;
+            }
+        }
+    }
+    if invalid > 0 {
+        eprintln!("{} invalid values; no fingerprint written", invalid);
+        return Ok(false);
+    }
+
+    let mut hasher = blake3::Hasher::new();
+    for id in &ids {
+        hasher.update(id);
+    }
+    let digest = hasher.finalize().to_hex().to_string();
+    out.record(
+        Record::new(digest.green().to_string())
+            .field("fingerprint", digest)
+            .field("algorithm", "blake3")
+            .field("ids", ids.len()),
+    )?;
+    Ok(true)
+}
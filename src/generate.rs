@@ -0,0 +1,706 @@
+//! Random UUID / B58UUID generation.
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::ValueEnum;
+use colored::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use regex::Regex;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::output::{self, OutputOptions, OutputWriter, Record};
+use crate::timestamp;
+
+/// Where `generate` draws its random bytes from.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RngKind {
+    /// The operating system's CSPRNG through `getrandom`
+    Os,
+    /// StdRng (ChaCha12) seeded with --seed or --seed-file; reproducible,
+    /// not for real IDs
+    Seeded,
+}
+
+/// UUID version `generate` produces.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum UuidVersion {
+    /// Random (v4)
+    #[value(name = "4")]
+    V4,
+    /// SHA-1 of a namespace and a name (v5): the same pair always gives the
+    /// same UUID
+    #[value(name = "5")]
+    V5,
+    /// Unix milliseconds followed by random bits (v7), for time-ordered keys
+    #[value(name = "7")]
+    V7,
+}
+
+/// `--namespace` for v5 UUIDs: one of the well-known namespaces of RFC 9562
+/// by name, or any UUID or B58UUID.
+pub fn parse_namespace(namespace: &str) -> Result<uuid::Uuid, String> {
+    match namespace.to_ascii_lowercase().as_str() {
+        "dns" => Ok(uuid::Uuid::NAMESPACE_DNS),
+        "url" => Ok(uuid::Uuid::NAMESPACE_URL),
+        "oid" => Ok(uuid::Uuid::NAMESPACE_OID),
+        "x500" => Ok(uuid::Uuid::NAMESPACE_X500),
+        // Short Base58 strings decode too, so only a full B58UUID counts
+        _ if namespace.len() == 22 => crate::alphabet::decode(namespace).map_err(|e| e.to_string()),
+        _ => crate::encoding::Encoding::Uuid
+            .decode(namespace)
+            .map_err(|_| {
+                format!(
+                    "{} is not dns, url, oid, x500, a UUID or a B58UUID",
+                    namespace
+                )
+            }),
+    }
+}
+
+/// Bytes of seed StdRng takes: ChaCha12 uses a 256-bit key.
+pub const SEED_LEN: usize = 32;
+
+/// Seed for `--rng seeded`.
+#[derive(Clone, Copy)]
+pub enum Seed {
+    /// `--seed`, expanded to a full seed by `SeedableRng::seed_from_u64`
+    Number(u64),
+    /// `--seed-file`: the ChaCha12 key itself
+    Bytes([u8; SEED_LEN]),
+}
+
+impl Seed {
+    /// The first `SEED_LEN` bytes of `path`; any further bytes are ignored.
+    pub fn from_file(path: &str) -> Result<Seed> {
+        let bytes = fs::read(path).context(format!("Failed to read seed file: {}", path))?;
+        if bytes.len() < SEED_LEN {
+            bail!(
+                "Seed file {} has {} bytes; the seeded RNG (ChaCha12) needs {}",
+                path,
+                bytes.len(),
+                SEED_LEN
+            );
+        }
+        let mut seed = [0u8; SEED_LEN];
+        seed.copy_from_slice(&bytes[..SEED_LEN]);
+        Ok(Seed::Bytes(seed))
+    }
+
+    fn rng(self) -> StdRng {
+        match self {
+            Seed::Number(seed) => StdRng::seed_from_u64(seed),
+            Seed::Bytes(seed) => StdRng::from_seed(seed),
+        }
+    }
+
+    /// Seed for shard `i`: `seed` mixed with `i`, or for a seed file its
+    /// first 8 bytes (read as a little-endian integer) mixed with `i`. Adding
+    /// `i` instead would give shard 1 of seed 42 the stream of shard 0 of
+    /// seed 43.
+    fn shard(self, i: u64) -> Seed {
+        match self {
+            Seed::Number(seed) => Seed::Number(mix(seed, i)),
+            Seed::Bytes(mut seed) => {
+                let mut low = [0u8; 8];
+                low.copy_from_slice(&seed[..8]);
+                let low = mix(u64::from_le_bytes(low), i);
+                seed[..8].copy_from_slice(&low.to_le_bytes());
+                Seed::Bytes(seed)
+            }
+        }
+    }
+}
+
+/// `seed` combined with the shard index `i` through SplitMix64's finalizer,
+/// so related pairs (adjacent seeds, adjacent shards) end up far apart.
+fn mix(seed: u64, i: u64) -> u64 {
+    splitmix64(seed ^ splitmix64(i))
+}
+
+/// One step of SplitMix64: a bijection on `u64` that scatters nearby inputs.
+fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// The OS facility `getrandom` uses on this platform.
+fn os_backend() -> &'static str {
+    if cfg!(any(target_os = "linux", target_os = "android")) {
+        "getrandom(2)"
+    } else if cfg!(any(target_os = "macos", target_os = "ios")) {
+        "getentropy(2)"
+    } else if cfg!(windows) {
+        "ProcessPrng"
+    } else if cfg!(any(
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd"
+    )) {
+        "getrandom(2)/getentropy(2)"
+    } else {
+        "the platform CSPRNG"
+    }
+}
+
+/// One line naming the random source, for `--verbose`.
+pub fn describe_rng(kind: RngKind, seed: Option<Seed>) -> String {
+    match (kind, seed) {
+        (RngKind::Seeded, Some(Seed::Number(seed))) => {
+            format!("rng: seeded (StdRng/ChaCha12, seed {})", seed)
+        }
+        (RngKind::Seeded, Some(Seed::Bytes(seed))) => {
+            let hex: String = seed.iter().map(|b| format!("{:02x}", b)).collect();
+            format!("rng: seeded (StdRng/ChaCha12, key {})", hex)
+        }
+        _ => format!("rng: os (getrandom via {})", os_backend()),
+    }
+}
+
+/// Random source for one generation run (or one shard), optionally watching
+/// for repeated values.
+pub struct UuidSource {
+    /// Seeded generator, or `None` for the OS CSPRNG.
+    rng: Option<Box<StdRng>>,
+    /// Values produced so far, kept only with `--warn-duplicates`.
+    seen: Option<HashSet<uuid::Uuid>>,
+    produced: usize,
+    /// `--match`: only values whose B58UUID matches are produced.
+    pattern: Option<Pattern>,
+    version: UuidVersion,
+}
+
+/// A `--match` constraint and the work spent satisfying it.
+struct Pattern {
+    regex: Regex,
+    /// Candidates to try per value before giving up.
+    max_attempts: u64,
+    /// Candidates tried so far, over all values.
+    attempts: u64,
+}
+
+impl UuidSource {
+    pub fn new(kind: RngKind, seed: Option<Seed>) -> Self {
+        let rng = match (kind, seed) {
+            (RngKind::Seeded, Some(seed)) => Some(Box::new(seed.rng())),
+            _ => None,
+        };
+        UuidSource {
+            rng,
+            seen: None,
+            produced: 0,
+            pattern: None,
+            version: UuidVersion::V4,
+        }
+    }
+
+    /// Produce `version` UUIDs. v7 values come from the OS CSPRNG whatever
+    /// the `RngKind`, since their timestamp makes them unreproducible anyway.
+    pub fn version(mut self, version: UuidVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Draw candidates until one's B58UUID matches `regex`, trying at most
+    /// `max_attempts` per value.
+    pub fn matching(mut self, regex: Regex, max_attempts: u64) -> Self {
+        self.pattern = Some(Pattern {
+            regex,
+            max_attempts,
+            attempts: 0,
+        });
+        self
+    }
+
+    /// Candidates tried under `--match`, if it is in use.
+    pub fn attempts(&self) -> Option<u64> {
+        self.pattern.as_ref().map(|pattern| pattern.attempts)
+    }
+
+    /// Values produced so far.
+    pub fn produced(&self) -> usize {
+        self.produced
+    }
+
+    fn random_uuid(&mut self) -> uuid::Uuid {
+        match (self.version, &mut self.rng) {
+            // The uuid crate keeps a process-wide counter behind now_v7, so
+            // values stay strictly increasing even within one millisecond
+            (UuidVersion::V7, _) => uuid::Uuid::now_v7(),
+            (UuidVersion::V4, Some(rng)) => uuid::Builder::from_random_bytes(rng.gen()).into_uuid(),
+            (UuidVersion::V4, None) => uuid::Uuid::new_v4(),
+            (UuidVersion::V5, _) => unreachable!("v5 UUIDs are named, not drawn"),
+        }
+    }
+
+    /// Warn on stderr whenever a value repeats one produced earlier.
+    pub fn warn_duplicates(mut self) -> Self {
+        self.seen = Some(HashSet::new());
+        self
+    }
+
+    fn next_uuid(&mut self) -> Result<uuid::Uuid> {
+        let uuid = match self.pattern.take() {
+            None => self.random_uuid(),
+            Some(mut pattern) => {
+                let mut found = None;
+                for _ in 0..pattern.max_attempts {
+                    let candidate = self.random_uuid();
+                    pattern.attempts += 1;
+                    if pattern.regex.is_match(&crate::alphabet::encode(candidate)) {
+                        found = Some(candidate);
+                        break;
+                    }
+                }
+                let (regex, max_attempts) = (pattern.regex.to_string(), pattern.max_attempts);
+                self.pattern = Some(pattern);
+                match found {
+                    Some(uuid) => uuid,
+                    None => bail!(
+                        "No B58UUID matched /{}/ in {} attempts (raise --max-attempts?)",
+                        regex,
+                        max_attempts
+                    ),
+                }
+            }
+        };
+        self.produced += 1;
+        if let Some(seen) = &mut self.seen {
+            if !seen.insert(uuid) {
+                eprintln!(
+                    "{} duplicate value #{}: {}",
+                    "Warning:".yellow().bold(),
+                    self.produced,
+                    crate::alphabet::encode(uuid)
+                );
+            }
+        }
+        Ok(uuid)
+    }
+}
+
+/// Reasons `--match <REGEX>` may never (or only very slowly) match a
+/// B58UUID written in `digits`, judged from the literal characters in the
+/// pattern. This is a heuristic: it ignores character classes and
+/// alternation.
+pub fn match_warnings(pattern: &str, digits: &[u8; 58]) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let mut literals = 0;
+    let mut chars = pattern.chars().peekable();
+    let mut in_class = false;
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => {
+                chars.next();
+            }
+            '[' => in_class = true,
+            ']' => in_class = false,
+            ch if in_class || !ch.is_ascii_alphanumeric() => {}
+            ch => {
+                // A quantifier that allows zero copies makes the literal optional
+                if !matches!(chars.peek(), Some('?' | '*')) {
+                    literals += 1;
+                }
+                if !digits.contains(&(ch as u8)) {
+                    warnings.push(format!("'{}' never appears in a B58UUID", ch));
+                }
+            }
+        }
+    }
+    // 58^21 * 32 > 2^128, so the first digit is at most 31 ('Y' in Bitcoin's
+    // order)
+    if let Some(ch) = pattern
+        .strip_prefix('^')
+        .and_then(|rest| rest.chars().next())
+    {
+        match digits.iter().position(|&c| c as char == ch) {
+            Some(digit) if digit > 31 => {
+                warnings.push(format!("a B58UUID never starts with '{}'", ch))
+            }
+            _ => {}
+        }
+    }
+    // Each fixed character cuts the odds by about 58
+    if literals >= 5 {
+        warnings.push(format!(
+            "{} fixed characters need roughly 58^{} (~{:.0e}) attempts per value",
+            literals,
+            literals,
+            58f64.powi(literals)
+        ));
+    }
+    warnings
+}
+
+/// What text output shows of each generated value; structured formats carry
+/// both forms either way.
+pub enum Shown {
+    B58uuid,
+    Uuid,
+    /// `<uuid><delimiter><b58uuid>`, for loading mapping tables
+    Both(String),
+}
+
+/// Emit `count` values from `source`.
+pub fn generate(
+    out: &mut dyn OutputWriter,
+    count: usize,
+    shown: &Shown,
+    source: &mut UuidSource,
+) -> Result<()> {
+    for _ in 0..count {
+        out.record(uuid_record(source.next_uuid()?, shown))?;
+    }
+    Ok(())
+}
+
+fn uuid_record(uuid: uuid::Uuid, shown: &Shown) -> Record {
+    let b58 = crate::alphabet::encode(uuid);
+    let text = match shown {
+        Shown::B58uuid => b58.clone(),
+        Shown::Uuid => uuid.to_string(),
+        Shown::Both(delimiter) => format!("{}{}{}", uuid, delimiter, b58),
+    };
+    Record::new(text.green().to_string())
+        .field("b58uuid", b58)
+        .field("uuid", uuid.to_string())
+}
+
+/// Emit the v5 UUID of `namespace` and each of `names`, in order.
+pub fn generate_named(
+    out: &mut dyn OutputWriter,
+    namespace: uuid::Uuid,
+    names: &[String],
+    shown: &Shown,
+) -> Result<()> {
+    for name in names {
+        let uuid = uuid::Uuid::new_v5(&namespace, name.as_bytes());
+        out.record(uuid_record(uuid, shown).field("name", name.as_str()))?;
+    }
+    Ok(())
+}
+
+/// Emit one value every `interval`, flushing each so a consumer sees it right
+/// away; `count` 0 keeps going until Ctrl-C. Ctrl-C stops between values, so
+/// the output always ends on a complete record.
+pub fn generate_interval(
+    out: &mut dyn OutputWriter,
+    count: usize,
+    shown: &Shown,
+    source: &mut UuidSource,
+    interval: Duration,
+) -> Result<()> {
+    let (stop_tx, stop_rx) = mpsc::channel();
+    ctrlc::set_handler(move || {
+        let _ = stop_tx.send(());
+    })?;
+
+    let mut emitted = 0;
+    loop {
+        generate(out, 1, shown, source)?;
+        out.flush()?;
+        emitted += 1;
+        if emitted == count {
+            return Ok(());
+        }
+        match stop_rx.recv_timeout(interval) {
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            _ => return Ok(()),
+        }
+    }
+}
+
+/// Values written between flushes under `--infinite`: few enough that a
+/// reader such as `head -n` sees output promptly, enough to keep writes to a
+/// file batched.
+const INFINITE_FLUSH_EVERY: u64 = 256;
+
+/// Emit values until Ctrl-C or until the reader goes away (a broken pipe,
+/// which surfaces as an error for the caller to treat as a normal end).
+/// Output is flushed every few hundred values and on Ctrl-C, so it always
+/// ends on a complete record.
+pub fn generate_infinite(
+    out: &mut dyn OutputWriter,
+    shown: &Shown,
+    source: &mut UuidSource,
+) -> Result<()> {
+    let (stop_tx, stop_rx) = mpsc::channel();
+    ctrlc::set_handler(move || {
+        let _ = stop_tx.send(());
+    })?;
+
+    let mut emitted: u64 = 0;
+    while stop_rx.try_recv().is_err() {
+        generate(out, 1, shown, source)?;
+        emitted += 1;
+        if emitted.is_multiple_of(INFINITE_FLUSH_EVERY) {
+            out.flush()?;
+        }
+    }
+    out.flush()
+}
+
+/// `--duration` seconds, fractions allowed (e.g. `0.5`).
+pub fn parse_duration(secs: &str) -> Result<Duration, String> {
+    secs.parse::<f64>()
+        .ok()
+        .filter(|secs| secs.is_finite() && *secs > 0.0)
+        .map(Duration::from_secs_f64)
+        .ok_or_else(|| format!("{} is not a positive number of seconds", secs))
+}
+
+/// Emit values until `duration` has elapsed, at most `rate` per second when
+/// given (each flushed, like `generate_interval`) or as fast as the output
+/// takes them. Ctrl-C stops early between values. How many were emitted, and
+/// over how long, is reported on stderr.
+pub fn generate_for(
+    out: &mut dyn OutputWriter,
+    shown: &Shown,
+    source: &mut UuidSource,
+    duration: Duration,
+    rate: Option<u32>,
+) -> Result<()> {
+    let (stop_tx, stop_rx) = mpsc::channel();
+    ctrlc::set_handler(move || {
+        let _ = stop_tx.send(());
+    })?;
+
+    let start = Instant::now();
+    let deadline = start + duration;
+    let mut emitted: u64 = 0;
+    let mut interrupted = false;
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            break;
+        }
+        if let Some(rate) = rate {
+            // Pace against the start rather than the previous value, so slow
+            // writes do not accumulate into a lower rate
+            let due = start + Duration::from_secs_f64(emitted as f64 / rate as f64);
+            if due > now {
+                match stop_rx.recv_timeout(due.min(deadline) - now) {
+                    Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                    _ => {
+                        interrupted = true;
+                        break;
+                    }
+                }
+            }
+        } else if stop_rx.try_recv().is_ok() {
+            interrupted = true;
+            break;
+        }
+        generate(out, 1, shown, source)?;
+        if rate.is_some() {
+            out.flush()?;
+        }
+        emitted += 1;
+    }
+    out.flush()?;
+
+    let elapsed = start.elapsed().as_secs_f64();
+    eprintln!(
+        "Generated {} values in {:.1}s ({:.0}/s){}",
+        emitted,
+        elapsed,
+        emitted as f64 / elapsed.max(f64::EPSILON),
+        if interrupted {
+            ", stopped early by Ctrl-C"
+        } else {
+            ""
+        }
+    );
+    Ok(())
+}
+
+/// Files written by `write_shards`: `<prefix><i>.txt` for each shard.
+pub fn shard_paths(prefix: &str, shards: usize) -> Vec<PathBuf> {
+    (0..shards)
+        .map(|i| PathBuf::from(format!("{}{}.txt", prefix, i)))
+        .collect()
+}
+
+/// Split `count` values across `shards` files named `<prefix><i>.txt`, one
+/// thread per shard. The remainder of an uneven split goes to the first
+/// shards, so sizes differ by at most one. Per-shard counts go to stderr.
+/// When seeded, shard `i` uses `seed.shard(i)` so every shard is
+/// reproducible.
+/// With `warn_duplicates`, each shard checks for repeats within itself.
+pub fn write_shards(
+    opts: OutputOptions,
+    count: usize,
+    shown: &Shown,
+    (kind, seed, version): (RngKind, Option<Seed>, UuidVersion),
+    warn_duplicates: bool,
+    shards: usize,
+    prefix: &str,
+) -> Result<()> {
+    let plan: Vec<(PathBuf, usize)> = shard_paths(prefix, shards)
+        .into_iter()
+        .enumerate()
+        .map(|(i, path)| (path, count / shards + usize::from(i < count % shards)))
+        .collect();
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = plan
+            .iter()
+            .enumerate()
+            .map(|(i, (path, size))| {
+                let mut source =
+                    UuidSource::new(kind, seed.map(|s| s.shard(i as u64))).version(version);
+                if warn_duplicates {
+                    source = source.warn_duplicates();
+                }
+                scope.spawn(move || {
+                    crate::write_to_file(path, |out| {
+                        let mut writer = output::writer(opts, Box::new(out), false);
+                        generate(&mut *writer, *size, shown, &mut source)?;
+                        writer.finish()
+                    })
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|_| Err(anyhow!("Shard writer thread panicked")))
+            })
+            .collect::<Result<Vec<()>>>()
+    })?;
+
+    for (path, size) in &plan {
+        eprintln!("{}: {}", path.display(), size);
+    }
+    Ok(())
+}
+
+/// Base timestamp for seeded tables, so `--seed` reproduces `created_at` too
+/// (2026-01-01T00:00:00Z).
+const SEEDED_EPOCH: i64 = 1_767_225_600;
+
+/// Emit `count` rows of fabricated test data. Column `id` is a B58UUID,
+/// `created_at` an RFC 3339 timestamp that increases by 1-3600 seconds per
+/// row (starting now, or at a fixed date when seeded), and every other
+/// column a placeholder `<column>_<6 hex digits>`.
+pub fn generate_table(
+    out: &mut dyn OutputWriter,
+    count: usize,
+    columns: &[String],
+    seed: Option<u64>,
+) -> Result<()> {
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let mut created_at = match seed {
+        Some(_) => SEEDED_EPOCH,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(SEEDED_EPOCH),
+    };
+
+    for _ in 0..count {
+        created_at += rng.gen_range(1..=3600);
+        let mut record = Record::new(String::new());
+        for column in columns {
+            let value = match column.as_str() {
+                "id" => {
+                    let uuid = uuid::Builder::from_random_bytes(rng.gen()).into_uuid();
+                    crate::alphabet::encode(uuid)
+                }
+                "created_at" => timestamp::format_rfc3339(created_at, 0),
+                other => format!("{}_{:06x}", other, rng.gen_range(0..0x100_0000u32)),
+            };
+            record = record.field(column.as_str(), value);
+        }
+        out.record(record)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shard_seed(seed: u64, i: u64) -> u64 {
+        match Seed::Number(seed).shard(i) {
+            Seed::Number(seed) => seed,
+            Seed::Bytes(_) => unreachable!("a number stays a number"),
+        }
+    }
+
+    #[test]
+    fn shard_seeds_do_not_collide_across_seeds() {
+        let mut seen = HashSet::new();
+        for seed in 0..256 {
+            for i in 0..32 {
+                assert!(
+                    seen.insert(shard_seed(seed, i)),
+                    "seed {} shard {}",
+                    seed,
+                    i
+                );
+            }
+        }
+        assert_ne!(shard_seed(42, 1), shard_seed(43, 0));
+    }
+
+    #[test]
+    fn namespaces_by_name_and_value() {
+        assert_eq!(parse_namespace("DNS"), Ok(uuid::Uuid::NAMESPACE_DNS));
+        assert_eq!(parse_namespace("x500"), Ok(uuid::Uuid::NAMESPACE_X500));
+        assert_eq!(
+            parse_namespace("6ba7b811-9dad-11d1-80b4-00c04fd430c8"),
+            Ok(uuid::Uuid::NAMESPACE_URL)
+        );
+        assert!(parse_namespace("bogus").is_err());
+    }
+
+    #[test]
+    fn v5_matches_the_rfc_vector() {
+        let uuid = uuid::Uuid::new_v5(&parse_namespace("dns").unwrap(), b"www.example.com");
+        assert_eq!(uuid.to_string(), "2ed6657d-e927-568b-95e1-2665a8aea6a2");
+    }
+
+    #[test]
+    fn match_warnings_follow_the_alphabet() {
+        use b58uuid_cli::Alphabet;
+        let start = |pattern, alphabet: Alphabet| match_warnings(pattern, alphabet.digits());
+        // 'a' is digit 33 in Bitcoin's order but 9 in Flickr's, and 'X' the
+        // other way round.
+        assert_eq!(
+            start("^a", Alphabet::Bitcoin),
+            ["a B58UUID never starts with 'a'"]
+        );
+        assert!(start("^a", Alphabet::Flickr).is_empty());
+        assert!(start("^X", Alphabet::Bitcoin).is_empty());
+        assert_eq!(
+            start("^X", Alphabet::Flickr),
+            ["a B58UUID never starts with 'X'"]
+        );
+        assert_eq!(
+            start("0", Alphabet::Ripple),
+            ["'0' never appears in a B58UUID"]
+        );
+    }
+
+    #[test]
+    fn seed_file_shards_differ() {
+        let key = |i| match Seed::Bytes([7; SEED_LEN]).shard(i) {
+            Seed::Bytes(key) => key,
+            Seed::Number(_) => unreachable!("a key stays a key"),
+        };
+        assert_ne!(key(0), key(1));
+        assert_eq!(key(0)[8..], [7; SEED_LEN - 8]);
+    }
+}
@@ -0,0 +1,640 @@
+//! Where a command reads its values from.
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::ValueEnum;
+use colored::*;
+use flate2::read::GzDecoder;
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read};
+use std::sync::mpsc::{self, SyncSender};
+use std::sync::OnceLock;
+use std::thread;
+
+use crate::meter::Counted;
+
+/// Characters `--trim-chars` strips from both ends of every value, on top of
+/// whitespace.
+static TRIM_CHARS: OnceLock<Vec<char>> = OnceLock::new();
+
+pub fn set_trim_chars(chars: &str) {
+    let _ = TRIM_CHARS.set(chars.chars().collect());
+}
+
+/// `value` without leading and trailing whitespace or `--trim-chars`, in any
+/// mix: `"550e8400-...",` with `--trim-chars '",'` is the bare UUID.
+pub fn trim(value: &str) -> &str {
+    let chars = TRIM_CHARS.get().map(Vec::as_slice).unwrap_or_default();
+    value.trim_matches(|ch: char| ch.is_whitespace() || chars.contains(&ch))
+}
+
+/// Values of batch input, one per line in the text format.
+pub type Lines = Box<dyn Iterator<Item = Result<String>>>;
+
+/// Values of batch input paired with the name of the file each came from.
+pub type SourcedLines = Box<dyn Iterator<Item = Result<(String, String)>>>;
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InputFormat {
+    /// Pick from the file extension: .json, .ndjson/.jsonl, .csv, else text
+    Auto,
+    /// One value per line
+    Text,
+    /// An array of strings or objects
+    Json,
+    /// One JSON string or object per line
+    Ndjson,
+    /// Comma-separated values with a header row
+    Csv,
+}
+
+impl InputFormat {
+    /// Resolve `auto` from the extension of `path`; stdin is always text.
+    pub fn resolve(self, path: Option<&str>) -> InputFormat {
+        if self != InputFormat::Auto {
+            return self;
+        }
+        let extension = path
+            .and_then(|p| std::path::Path::new(p).extension())
+            .map(|e| e.to_string_lossy().to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("json") => InputFormat::Json,
+            Some("ndjson") | Some("jsonl") => InputFormat::Ndjson,
+            Some("csv") => InputFormat::Csv,
+            _ => InputFormat::Text,
+        }
+    }
+}
+
+/// A byte range `start:end` (end exclusive) of each line holding the value,
+/// for fixed-width files.
+#[derive(Clone, Copy)]
+pub struct ColumnRange {
+    start: usize,
+    end: usize,
+}
+
+impl std::str::FromStr for ColumnRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let (start, end) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected START:END, got '{}'", s))?;
+        let start: usize = start
+            .trim()
+            .parse()
+            .map_err(|e| format!("bad start: {}", e))?;
+        let end: usize = end.trim().parse().map_err(|e| format!("bad end: {}", e))?;
+        if start >= end {
+            return Err(format!("start {} must be less than end {}", start, end));
+        }
+        Ok(ColumnRange { start, end })
+    }
+}
+
+impl ColumnRange {
+    /// The part of `line` in the range.
+    pub fn slice<'a>(&self, line: &'a str) -> Result<&'a str, String> {
+        line.get(self.start..self.end).ok_or_else(|| {
+            format!(
+                "columns {}:{} are out of bounds for a line of {} bytes",
+                self.start,
+                self.end,
+                line.len()
+            )
+        })
+    }
+
+    /// `line` with the range replaced by `value`, padded with spaces to the
+    /// width of the range so later columns stay in place (a longer value
+    /// pushes them right).
+    pub fn splice(&self, line: &str, value: &str) -> String {
+        let width = self.end - self.start;
+        format!(
+            "{}{:<width$}{}",
+            &line[..self.start],
+            value,
+            &line[self.end..],
+            width = width
+        )
+    }
+}
+
+/// A `--where COL=VALUE` row filter for CSV input.
+#[derive(Clone)]
+pub struct WhereFilter {
+    pub column: String,
+    pub value: String,
+}
+
+impl std::str::FromStr for WhereFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.split_once('=') {
+            Some((column, value)) if !column.is_empty() => Ok(WhereFilter {
+                column: column.to_string(),
+                value: value.to_string(),
+            }),
+            _ => Err(format!("expected COL=VALUE, got '{}'", s)),
+        }
+    }
+}
+
+/// Input settings taken from the global flags.
+#[derive(Clone)]
+pub struct InputOptions {
+    pub format: InputFormat,
+    /// JSON key or CSV column holding the value; the first one when unset.
+    pub field: Option<String>,
+    /// Split text input on any whitespace rather than only on line breaks.
+    pub whitespace: bool,
+    /// Skip `#` comment lines of text input; unset, they are skipped in files
+    /// but not on stdin.
+    pub comments: Option<bool>,
+    /// Split text input on NUL bytes instead of line breaks (`--null`).
+    pub null: bool,
+}
+
+/// Input for commands that convert or check values.
+pub enum InputSource {
+    /// A single value given on the command line.
+    Value(String),
+    /// A file with one value per line.
+    File(String),
+    /// Several files (`--file` repeated), read one after the other.
+    Files(Vec<String>),
+    /// Standard input, one value per line.
+    Stdin,
+    /// Every text file in a tar archive (optionally gzipped), one value per
+    /// line.
+    Tar(String),
+}
+
+impl InputSource {
+    /// Pick the input source for a command. Precedence is the same for every
+    /// command: a positional value wins, then `--file`, then stdin.
+    pub fn resolve(value: Option<String>, file: Option<String>) -> Self {
+        match (value, file) {
+            (Some(value), _) => InputSource::Value(value),
+            (None, Some(file_path)) => InputSource::File(file_path),
+            (None, None) => InputSource::Stdin,
+        }
+    }
+
+    /// Like [`InputSource::resolve`], for commands whose `--file` may be
+    /// repeated: two or more files are read one after the other.
+    pub fn resolve_files(value: Option<String>, mut files: Vec<String>) -> Self {
+        if value.is_none() && files.len() > 1 {
+            return InputSource::Files(files);
+        }
+        InputSource::resolve(value, files.pop())
+    }
+
+    /// Read the values of the source in the format selected by `opts`. A
+    /// positional value is a single value.
+    pub fn lines(self, opts: &InputOptions) -> Result<Lines> {
+        let path = match &self {
+            InputSource::File(file_path) => Some(file_path.as_str()),
+            _ => None,
+        };
+        if let InputSource::Tar(_) | InputSource::Files(_) = self {
+            let lines = self
+                .sourced_lines(opts)?
+                .map(|line| line.map(|(_, line)| line));
+            return Ok(Box::new(lines));
+        }
+        let field = opts.field.clone();
+        match opts.format.resolve(path) {
+            _ if matches!(self, InputSource::Value(_)) => self.text_lines(opts),
+            InputFormat::Auto | InputFormat::Text if opts.whitespace => {
+                Ok(tokenize(self.uncommented_lines(opts)?, |line| {
+                    line.split_whitespace().map(str::to_string).collect()
+                }))
+            }
+            InputFormat::Auto | InputFormat::Text => self.uncommented_lines(opts),
+            InputFormat::Ndjson => {
+                let values = self
+                    .text_lines(opts)?
+                    .enumerate()
+                    .filter(|(_, line)| !matches!(line, Ok(l) if l.trim().is_empty()))
+                    .map(move |(i, line)| {
+                        let item: Value = serde_json::from_str(&line?)
+                            .context(format!("Invalid JSON at line {}", i + 1))?;
+                        json_value(&item, field.as_deref(), i + 1)
+                    });
+                Ok(Box::new(values))
+            }
+            InputFormat::Json => {
+                let mut content = String::new();
+                self.reader()?
+                    .read_to_string(&mut content)
+                    .context("Failed to read JSON input")?;
+                let items = match serde_json::from_str(&content).context("Invalid JSON input")? {
+                    Value::Array(items) => items,
+                    _ => bail!("JSON input must be an array"),
+                };
+                let values: Vec<Result<String>> = items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| json_value(item, field.as_deref(), i + 1))
+                    .collect();
+                Ok(Box::new(values.into_iter()))
+            }
+            InputFormat::Csv => {
+                let mut reader = csv::ReaderBuilder::new()
+                    .flexible(true)
+                    .from_reader(self.reader()?);
+                let headers = reader.headers().context("Failed to read CSV header")?;
+                let column = match field.as_deref() {
+                    Some(name) => headers
+                        .iter()
+                        .position(|h| h == name)
+                        .ok_or_else(|| anyhow!("CSV input has no column '{}'", name))?,
+                    None => 0,
+                };
+                let values = reader.into_records().map(move |row| {
+                    let row = row.context("Failed to read CSV row")?;
+                    Ok(row.get(column).unwrap_or_default().to_string())
+                });
+                Ok(Box::new(values))
+            }
+        }
+    }
+
+    /// Like [`InputSource::lines`], with each value tagged by its file: the
+    /// member name for archives, the path for `--file` and `stdin` otherwise.
+    pub fn sourced_lines(self, opts: &InputOptions) -> Result<SourcedLines> {
+        let name = match &self {
+            InputSource::Tar(path) if opts.whitespace => {
+                return Ok(tokenize(tar_lines(path)?, |(name, line)| {
+                    line.split_whitespace()
+                        .map(|token| (name.clone(), token.to_string()))
+                        .collect()
+                }));
+            }
+            InputSource::Tar(path) => return tar_lines(path),
+            InputSource::Files(paths) => return Ok(files_lines(paths.clone(), opts.clone())),
+            InputSource::File(path) => path.clone(),
+            InputSource::Value(_) | InputSource::Stdin => "stdin".to_string(),
+        };
+        let lines = self.lines(opts)?;
+        Ok(Box::new(
+            lines.map(move |line| line.map(|line| (name.clone(), line))),
+        ))
+    }
+
+    /// [`InputSource::text_lines`] without the `#` comment lines, if
+    /// `opts` (or, by default, reading a file) says to skip them.
+    fn uncommented_lines(self, opts: &InputOptions) -> Result<Lines> {
+        let skip = opts
+            .comments
+            .unwrap_or(matches!(self, InputSource::File(_)));
+        let lines = self.text_lines(opts)?;
+        if !skip {
+            return Ok(lines);
+        }
+        Ok(Box::new(lines.filter(
+            |line| !matches!(line, Ok(l) if l.trim_start().starts_with('#')),
+        )))
+    }
+
+    fn text_lines(self, opts: &InputOptions) -> Result<Lines> {
+        let (reader, source): (Box<dyn BufRead>, String) = match self {
+            InputSource::Tar(_) => unreachable!("archives are read through sourced_lines"),
+            InputSource::Files(_) => unreachable!("file lists are read through sourced_lines"),
+            InputSource::Value(value) => return Ok(Box::new(std::iter::once(Ok(value)))),
+            InputSource::File(file_path) => {
+                let file = File::open(&file_path)
+                    .context(format!("Failed to read file: {}", file_path))?;
+                (Box::new(BufReader::new(Counted(file))), file_path)
+            }
+            InputSource::Stdin => (
+                Box::new(BufReader::new(Counted(io::stdin()))),
+                "stdin".to_string(),
+            ),
+        };
+        Ok(if opts.null {
+            read_nul_separated(reader, source)
+        } else {
+            read_lines(reader, source)
+        })
+    }
+
+    /// Bytes of input in the source's files, for `--progress`; `None` for
+    /// standard input and positional values.
+    pub fn size(&self) -> Option<u64> {
+        let size = |path: &String| fs::metadata(path).map_or(0, |meta| meta.len());
+        match self {
+            InputSource::File(path) | InputSource::Tar(path) => Some(size(path)),
+            InputSource::Files(paths) => Some(paths.iter().map(size).sum()),
+            InputSource::Value(_) | InputSource::Stdin => None,
+        }
+    }
+
+    /// The raw bytes of the source.
+    pub fn reader(self) -> Result<Box<dyn Read>> {
+        match self {
+            InputSource::Value(value) => Ok(Box::new(io::Cursor::new(value.into_bytes()))),
+            InputSource::File(file_path) => Ok(Box::new(Counted(
+                File::open(&file_path).context(format!("Failed to read file: {}", file_path))?,
+            ))),
+            InputSource::Stdin => Ok(Box::new(Counted(io::stdin()))),
+            InputSource::Tar(_) => unreachable!("archives are read through sourced_lines"),
+            InputSource::Files(_) => bail!("This input can only be read from a single --file"),
+        }
+    }
+}
+
+/// The values of each file in `paths` in turn, tagged with its path. A file
+/// that cannot be read is reported on stderr and skipped, so one missing
+/// file does not abort the rest of the run.
+fn files_lines(paths: Vec<String>, opts: InputOptions) -> SourcedLines {
+    Box::new(paths.into_iter().flat_map(move |path| {
+        match InputSource::File(path.clone()).sourced_lines(&opts) {
+            Ok(lines) => lines,
+            Err(e) => {
+                eprintln!("{} {:#} (skipped)", "Error:".red().bold(), e);
+                Box::new(std::iter::empty())
+            }
+        }
+    }))
+}
+
+/// Replace each line by the values `split` finds in it, for `--whitespace`.
+/// Read errors pass through unchanged.
+fn tokenize<T: 'static>(
+    lines: Box<dyn Iterator<Item = Result<T>>>,
+    split: fn(T) -> Vec<T>,
+) -> Box<dyn Iterator<Item = Result<T>>> {
+    Box::new(lines.flat_map(move |line| match line {
+        Ok(line) => split(line).into_iter().map(Ok).collect(),
+        Err(e) => vec![Err(e)],
+    }))
+}
+
+/// The value carried by one JSON item: the item itself if it is a string,
+/// otherwise the `field` (or first) member of an object.
+fn json_value(item: &Value, field: Option<&str>, index: usize) -> Result<String> {
+    let value = match (item, field) {
+        (Value::String(s), _) => return Ok(s.clone()),
+        (Value::Object(map), Some(name)) => map.get(name),
+        (Value::Object(map), None) => map.values().next(),
+        _ => None,
+    };
+    match value {
+        Some(Value::String(s)) => Ok(s.clone()),
+        _ => match field {
+            Some(name) => bail!("JSON item {} has no string field '{}'", index, name),
+            None => bail!(
+                "JSON item {} is not a string or an object whose first field is a string",
+                index
+            ),
+        },
+    }
+}
+
+/// Split a byte stream into lines.
+///
+/// Every streaming source must go through this rather than decoding
+/// fixed-size chunks: a chunk boundary can fall inside a multibyte UTF-8
+/// character, so bytes are buffered up to each `\n` and only complete lines
+/// are decoded. A line that is not valid UTF-8 is reported with its line
+/// number. Trailing `\n` / `\r\n` is removed.
+pub fn read_lines<R>(mut reader: R, source: impl Into<String>) -> Lines
+where
+    R: BufRead + 'static,
+{
+    let source = source.into();
+    let mut line_number = 0usize;
+    Box::new(std::iter::from_fn(move || {
+        let mut buf = Vec::new();
+        match reader.read_until(b'\n', &mut buf) {
+            Ok(0) => None,
+            Ok(_) => {
+                line_number += 1;
+                if buf.ends_with(b"\n") {
+                    buf.pop();
+                    if buf.ends_with(b"\r") {
+                        buf.pop();
+                    }
+                }
+                Some(
+                    String::from_utf8(buf).map_err(|_| {
+                        anyhow!("Invalid UTF-8 in {} at line {}", source, line_number)
+                    }),
+                )
+            }
+            Err(e) => Some(Err(e).context(format!("Failed to read from {}", source))),
+        }
+    }))
+}
+
+/// Like [`read_lines`] for input separated by NUL bytes, as `find -print0`
+/// writes it. A final separator ends the last value rather than starting an
+/// empty one.
+pub fn read_nul_separated<R>(mut reader: R, source: impl Into<String>) -> Lines
+where
+    R: BufRead + 'static,
+{
+    let source = source.into();
+    let mut value_number = 0usize;
+    Box::new(std::iter::from_fn(move || {
+        let mut buf = Vec::new();
+        match reader.read_until(b'\0', &mut buf) {
+            Ok(0) => None,
+            Ok(_) => {
+                value_number += 1;
+                if buf.ends_with(b"\0") {
+                    buf.pop();
+                }
+                Some(
+                    String::from_utf8(buf).map_err(|_| {
+                        anyhow!("Invalid UTF-8 in {} at value {}", source, value_number)
+                    }),
+                )
+            }
+            Err(e) => Some(Err(e).context(format!("Failed to read from {}", source))),
+        }
+    }))
+}
+
+/// Lines of every text member of the tar archive at `path`, gzipped or not
+/// (told apart by the gzip magic bytes). Members are read on a background
+/// thread so the archive is streamed rather than extracted; members that are
+/// not UTF-8 text are skipped with a warning.
+fn tar_lines(path: &str) -> Result<SourcedLines> {
+    let file = File::open(path).context(format!("Failed to read file: {}", path))?;
+    let mut reader = BufReader::new(Counted(file));
+    let gzipped = reader
+        .fill_buf()
+        .context(format!("Failed to read file: {}", path))?
+        .starts_with(&[0x1f, 0x8b]);
+    let reader: Box<dyn Read + Send> = if gzipped {
+        Box::new(GzDecoder::new(reader))
+    } else {
+        Box::new(reader)
+    };
+
+    let (tx, rx) = mpsc::sync_channel(1024);
+    let path = path.to_string();
+    thread::spawn(move || {
+        if let Err(e) = send_tar_lines(reader, &tx) {
+            let _ = tx.send(Err(e.context(format!("Failed to read archive: {}", path))));
+        }
+    });
+    Ok(Box::new(rx.into_iter()))
+}
+
+fn send_tar_lines(
+    reader: Box<dyn Read + Send>,
+    tx: &SyncSender<Result<(String, String)>>,
+) -> Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry.path()?.display().to_string();
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+        let text = match String::from_utf8(data) {
+            Ok(text) if !text.contains('\0') => text,
+            _ => {
+                eprintln!(
+                    "{} skipping non-text archive member {}",
+                    "Warning:".yellow().bold(),
+                    name
+                );
+                continue;
+            }
+        };
+        for line in text.lines() {
+            if tx.send(Ok((name.clone(), line.to_string()))).is_err() {
+                // The reader went away; nothing left to do
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn name(source: &InputSource) -> String {
+        match source {
+            InputSource::Value(value) => format!("value {}", value),
+            InputSource::File(path) => format!("file {}", path),
+            InputSource::Files(paths) => format!("files {}", paths.join(",")),
+            InputSource::Stdin => "stdin".to_string(),
+            InputSource::Tar(path) => format!("tar {}", path),
+        }
+    }
+
+    #[test]
+    fn value_wins_then_file_then_stdin() {
+        let value = || Some("v".to_string());
+        let file = || Some("f".to_string());
+        assert_eq!(name(&InputSource::resolve(value(), file())), "value v");
+        assert_eq!(name(&InputSource::resolve(None, file())), "file f");
+        assert_eq!(name(&InputSource::resolve(None, None)), "stdin");
+    }
+
+    #[test]
+    fn repeated_files_are_read_in_turn() {
+        let files = || vec!["a".to_string(), "b".to_string()];
+        assert_eq!(
+            name(&InputSource::resolve_files(None, files())),
+            "files a,b"
+        );
+        assert_eq!(
+            name(&InputSource::resolve_files(Some("v".to_string()), files())),
+            "value v"
+        );
+        assert_eq!(
+            name(&InputSource::resolve_files(None, vec!["a".to_string()])),
+            "file a"
+        );
+        assert_eq!(name(&InputSource::resolve_files(None, Vec::new())), "stdin");
+    }
+
+    /// Hands out at most `chunk` bytes per read, like a network stream.
+    struct Chunked {
+        data: Vec<u8>,
+        chunk: usize,
+    }
+
+    impl Read for Chunked {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.chunk.min(buf.len()).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data.drain(..n);
+            Ok(n)
+        }
+    }
+
+    fn collect(lines: Lines) -> Vec<String> {
+        lines.map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn multibyte_characters_survive_chunk_boundaries() {
+        let text = "aé€\n𝄞b\r\nçz\n";
+        // 2- to 4-byte characters fall across every possible boundary
+        for chunk in 1..=5 {
+            for capacity in 1..=5 {
+                let reader = Chunked {
+                    data: text.as_bytes().to_vec(),
+                    chunk,
+                };
+                let lines = read_lines(BufReader::with_capacity(capacity, reader), "test");
+                assert_eq!(collect(lines), ["aé€", "𝄞b", "çz"], "chunk {}", chunk);
+            }
+        }
+    }
+
+    #[test]
+    fn last_line_without_a_newline_is_kept() {
+        let data = b"a\r\nb\n\nc".to_vec();
+        for chunk in 1..=3 {
+            let reader = Chunked {
+                data: data.clone(),
+                chunk,
+            };
+            let lines = read_lines(BufReader::new(reader), "test");
+            assert_eq!(collect(lines), ["a", "b", "", "c"], "chunk {}", chunk);
+        }
+    }
+
+    /// The same line over and over, never ending.
+    struct Endless;
+
+    impl Read for Endless {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let line = b"550e8400-e29b-41d4-a716-446655440000\n";
+            let n = line.len().min(buf.len());
+            buf[..n].copy_from_slice(&line[..n]);
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn lines_stream_without_reading_to_the_end() {
+        // Reading the whole input first would never return.
+        let lines = read_lines(BufReader::new(Endless), "test");
+        assert_eq!(lines.take(3).filter(Result::is_ok).count(), 3);
+    }
+
+    #[test]
+    fn invalid_utf8_is_reported_with_its_line() {
+        let data = b"ok\n\xe2\x82\n".to_vec();
+        let mut lines = read_lines(BufReader::new(Chunked { data, chunk: 1 }), "test");
+        assert_eq!(lines.next().unwrap().unwrap(), "ok");
+        let error = lines.next().unwrap().unwrap_err();
+        assert_eq!(error.to_string(), "Invalid UTF-8 in test at line 2");
+    }
+}
@@ -0,0 +1,58 @@
+//! `inspect`: everything about one identifier, in either form, as a labeled
+//! block or (with `--format json`) one object with the same fields.
+
+use anyhow::Result;
+use colored::*;
+
+use crate::output::{self, OutputWriter, Record};
+
+/// Write the metadata of `value`, or an error if it is neither a UUID nor a
+/// B58UUID. Returns whether it was one.
+pub fn inspect(value: &str, out: &mut dyn OutputWriter) -> Result<bool> {
+    let value = crate::input::trim(value);
+    let Some((kind, uuid, b58)) = crate::classify(value) else {
+        out.error(value, &crate::invalid_reason(value))?;
+        return Ok(false);
+    };
+    let bytes = uuid.replace('-', "");
+    let version = crate::uuid_version(&uuid).filter(|_| !crate::is_nil(&uuid));
+    let variant = crate::uuid_variant(&uuid);
+    let timestamp = crate::uuid_timestamp(&uuid);
+    let nil = crate::is_nil(&uuid);
+    let uuid = output::uuid_case(&uuid);
+    let shown = |field: Option<String>| field.unwrap_or_else(|| "n/a".to_string());
+    let lines = [
+        ("Input", format!("{} ({})", value, kind)),
+        ("UUID", uuid.clone()),
+        ("B58UUID", b58.clone()),
+        (
+            "Version",
+            match version {
+                Some((number, name)) => format!("{} ({})", number, name),
+                None if nil => "n/a (nil UUID: all bits zero)".to_string(),
+                None => "n/a".to_string(),
+            },
+        ),
+        ("Variant", shown(variant.map(str::to_string))),
+        ("Bytes", bytes.clone()),
+        ("Timestamp", shown(timestamp.clone())),
+    ];
+    let text: Vec<String> = lines
+        .iter()
+        .map(|(label, field)| format!("{:<10} {}", format!("{}:", label), field.cyan()))
+        .collect();
+    out.record(
+        Record::new(text.join("\n"))
+            .field("input", value)
+            .field("type", kind)
+            .field("uuid", uuid)
+            .field("b58uuid", b58)
+            .field("version", version.map(|(number, _)| number))
+            .field("version_name", version.map(|(_, name)| name))
+            .field("variant", variant)
+            .field("bytes", bytes)
+            .field("timestamp", timestamp)
+            .field("nil", nil),
+    )?;
+    Ok(true)
+}
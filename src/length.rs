@@ -0,0 +1,70 @@
+//! Distribution of encoded lengths over a real dataset.
+//!
+//! Every B58UUID should be exactly 22 characters: UUIDs with leading zero
+//! bytes encode to a shorter number and must be padded with `1`. This report
+//! shows whether that holds for a given file and points at any value where
+//! it does not.
+
+use anyhow::Result;
+use colored::*;
+use std::collections::BTreeMap;
+
+use crate::input::Lines;
+use crate::output::{OutputWriter, Record};
+
+const EXPECTED_LEN: usize = 22;
+
+/// Encode every UUID in `lines` and write one record per output length.
+/// Values whose encoding is not 22 characters are warned about on stderr,
+/// followed by a min/max summary. Returns whether every length was 22.
+pub fn length_stats(lines: Lines, out: &mut dyn OutputWriter) -> Result<bool> {
+    let mut histogram: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut errors = 0;
+    for (index, line) in lines.enumerate() {
+        let line = line?;
+        let value = line.trim();
+        if value.is_empty() {
+            continue;
+        }
+        match b58uuid_cli::encode_uuid(value) {
+            Ok(encoded) => {
+                let len = encoded.chars().count();
+                if len != EXPECTED_LEN {
+                    eprintln!(
+                        "{} line {}: {} encodes to {} ({} chars)",
+                        "Warning:".yellow().bold(),
+                        index + 1,
+                        value,
+                        encoded,
+                        len
+                    );
+                }
+                *histogram.entry(len).or_default() += 1;
+            }
+            Err(_) => errors += 1,
+        }
+    }
+
+    for (&len, &count) in &histogram {
+        let mark = if len == EXPECTED_LEN {
+            "✓".green().bold()
+        } else {
+            "✗".red().bold()
+        };
+        out.record(
+            Record::new(format!("{} {} chars: {}", mark, len, count))
+                .field("length", len)
+                .field("count", count),
+        )?;
+    }
+
+    let encoded: usize = histogram.values().sum();
+    match (histogram.keys().next(), histogram.keys().next_back()) {
+        (Some(min), Some(max)) => eprintln!(
+            "Encoded {} UUIDs ({} not valid UUIDs): min {}, max {}",
+            encoded, errors, min, max
+        ),
+        _ => eprintln!("No valid UUIDs ({} not valid UUIDs)", errors),
+    }
+    Ok(histogram.keys().all(|&len| len == EXPECTED_LEN))
+}
@@ -0,0 +1,604 @@
+//! Typed helpers over the `b58uuid` codec used by the `b58uuid` CLI.
+//!
+//! The codec works on UUID strings and reports failures as messages, which
+//! callers holding a [`uuid::Uuid`] would have to format and parse again.
+//! [`encode`] and [`decode`] take and return the `Uuid` directly, and every
+//! function here fails with [`B58Error`], whose variants carry the details
+//! (such as the offending character and its index) as fields. The `_with`
+//! variants read and write B58UUIDs in another [`Alphabet`].
+
+use std::error::Error;
+use std::fmt;
+use uuid::Uuid;
+
+/// Why a value could not be converted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum B58Error {
+    /// Not a UUID string; the message says what is wrong with it
+    InvalidUuidFormat(String),
+    /// A non-hex character in a UUID string; `index` is where its pair of
+    /// digits starts, hyphens removed
+    InvalidHex { index: usize },
+    /// A non-ASCII character (e.g. a fullwidth digit) in a UUID string, at
+    /// character `index`
+    NonAsciiUuid { ch: char, index: usize },
+    /// Hyphens in a UUID string other than those of the 8-4-4-4-12 form
+    MisplacedHyphens,
+    /// A character outside the Base58 alphabet, at character `index`
+    InvalidBase58Character { ch: char, index: usize },
+    /// A non-ASCII character (e.g. a Unicode look-alike letter) in a Base58
+    /// string, at character `index`
+    NonAsciiBase58 { ch: char, index: usize },
+    /// An empty Base58 string
+    EmptyBase58,
+    /// More leading '1's (zero digits) than a B58UUID can have
+    TooManyLeadingOnes,
+    /// Any other malformed Base58 string
+    InvalidBase58(String),
+    /// A B58UUID that does not decode in the expected alphabet but does in
+    /// another, so was likely written in that one
+    WrongAlphabet {
+        expected: Alphabet,
+        likely: Alphabet,
+    },
+    /// A UUID string without 32 hex digits
+    WrongLength { expected: usize, got: usize },
+    /// A Base58 number too large for 128 bits
+    Overflow,
+    /// Malformed input in another compact encoding, such as Base62
+    InvalidEncoding {
+        encoding: &'static str,
+        message: String,
+    },
+}
+
+impl fmt::Display for B58Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            B58Error::InvalidUuidFormat(message) => write!(f, "Invalid UUID: {}", message),
+            B58Error::InvalidHex { index } => {
+                write!(f, "Invalid UUID: Invalid hex at position {}", index)
+            }
+            B58Error::NonAsciiUuid { ch, index } => write!(
+                f,
+                "Invalid UUID: non-ASCII character U+{:04X} at position {}; UUIDs are ASCII-only",
+                *ch as u32, index
+            ),
+            B58Error::MisplacedHyphens => write!(
+                f,
+                "Invalid UUID: Misplaced hyphens: expected 8-4-4-4-12 or none"
+            ),
+            B58Error::InvalidBase58Character { ch, index } => match look_alike(*ch) {
+                Some(suggestion) => write!(
+                    f,
+                    "Invalid Base58: Invalid character '{}' at position {}; did you mean '{}'? Base58 excludes 0/O/I/l",
+                    ch, index, suggestion
+                ),
+                None => write!(
+                    f,
+                    "Invalid Base58: Invalid character at position {}: {}",
+                    index, ch
+                ),
+            },
+            B58Error::NonAsciiBase58 { ch, index } => write!(
+                f,
+                "Invalid Base58: non-ASCII character U+{:04X} at position {}; Base58 is ASCII-only",
+                *ch as u32, index
+            ),
+            B58Error::EmptyBase58 => write!(f, "Invalid Base58: Empty Base58 string"),
+            B58Error::TooManyLeadingOnes => {
+                write!(f, "Invalid Base58: Too many leading '1' characters")
+            }
+            B58Error::InvalidBase58(message) => write!(f, "Invalid Base58: {}", message),
+            B58Error::WrongAlphabet { expected, likely } => write!(
+                f,
+                "Invalid Base58: too large for 128 bits in the {} alphabet, but decodes in {}",
+                expected.name(),
+                likely.name()
+            ),
+            B58Error::WrongLength { expected, got } => {
+                write!(f, "Invalid length: expected {}, got {}", expected, got)
+            }
+            B58Error::Overflow => {
+                write!(f, "Arithmetic overflow: value exceeds maximum UUID value")
+            }
+            B58Error::InvalidEncoding { encoding, message } => {
+                write!(f, "Invalid {}: {}", encoding, message)
+            }
+        }
+    }
+}
+
+impl Error for B58Error {}
+
+/// The Base58 digit most often meant by one of the four characters Base58
+/// leaves out because they are easy to misread: `0` and `O` for `o`, `I` and
+/// `l` for `1`.
+fn look_alike(ch: char) -> Option<char> {
+    match ch {
+        '0' | 'O' => Some('o'),
+        'I' | 'l' => Some('1'),
+        _ => None,
+    }
+}
+
+/// The functions here check input themselves before handing it to the
+/// codec, so its errors can only be passed on as they are.
+impl From<b58uuid::B58UUIDError> for B58Error {
+    fn from(error: b58uuid::B58UUIDError) -> Self {
+        use b58uuid::B58UUIDError::*;
+        match error {
+            InvalidUUID(message) => B58Error::InvalidUuidFormat(message),
+            InvalidBase58(message) => B58Error::InvalidBase58(message),
+            InvalidLength { expected, got } => B58Error::WrongLength { expected, got },
+            Overflow => B58Error::Overflow,
+        }
+    }
+}
+
+/// Order of the 58 Base58 digits. All three use the same characters (no
+/// `0`, `O`, `I` or `l`), only in different orders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Alphabet {
+    /// Bitcoin's, the standard one for B58UUIDs
+    #[default]
+    Bitcoin,
+    /// Ripple's (XRP Ledger)
+    Ripple,
+    /// Flickr's: lower case before upper case
+    Flickr,
+}
+
+impl Alphabet {
+    pub const ALL: [Alphabet; 3] = [Alphabet::Bitcoin, Alphabet::Ripple, Alphabet::Flickr];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Alphabet::Bitcoin => "bitcoin",
+            Alphabet::Ripple => "ripple",
+            Alphabet::Flickr => "flickr",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Alphabet> {
+        Alphabet::ALL
+            .into_iter()
+            .find(|alphabet| alphabet.name() == name)
+    }
+
+    /// The digits in order of value, from zero.
+    pub fn digits(self) -> &'static [u8; 58] {
+        match self {
+            Alphabet::Bitcoin => b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz",
+            Alphabet::Ripple => b"rpshnaf39wBUDNEGHJKLM4PQRST7VWXYZ2bcdeCg65jkm8oFqi1tuvAxyz",
+            Alphabet::Flickr => b"123456789abcdefghijkmnopqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ",
+        }
+    }
+}
+
+/// `b58` with each digit replaced by the digit of the same value in `to`.
+/// A character that is not a digit of `from` fails with its position.
+fn translate(b58: &str, from: Alphabet, to: Alphabet) -> Result<String, B58Error> {
+    if from == to {
+        return Ok(b58.to_string());
+    }
+    b58.chars()
+        .enumerate()
+        .map(|(index, ch)| {
+            from.digits()
+                .iter()
+                .position(|&digit| digit as char == ch)
+                .map(|value| to.digits()[value] as char)
+                .ok_or(B58Error::InvalidBase58Character { ch, index })
+        })
+        .collect()
+}
+
+/// The 22-character B58UUID of `uuid`. The length is fixed: a UUID with
+/// leading zero bytes (down to the nil UUID, `1111111111111111111111`) is
+/// left-padded with '1', the Base58 zero digit.
+pub fn encode(uuid: Uuid) -> String {
+    b58uuid::encode(uuid.as_bytes())
+}
+
+/// [`encode`] in `alphabet`, padded with its zero digit.
+pub fn encode_with(uuid: Uuid, alphabet: Alphabet) -> String {
+    translate(&encode(uuid), Alphabet::Bitcoin, alphabet).expect("the codec writes Bitcoin digits")
+}
+
+/// The 22-character B58UUID of a UUID given as its 16 bytes in network
+/// (big-endian) order, as database drivers and binary protocols hand them
+/// out. Padded like [`encode`].
+pub fn encode_bytes(bytes: &[u8; 16]) -> String {
+    b58uuid::encode(bytes)
+}
+
+/// The UUID a B58UUID stands for. The padded 22-character form is the one
+/// [`encode`] produces; like the codec, this also accepts the shorter form
+/// with leading '1's (zero digits) trimmed.
+pub fn decode(b58: &str) -> Result<Uuid, B58Error> {
+    decode_with(b58, Alphabet::Bitcoin)
+}
+
+/// [`decode`] for a B58UUID in `alphabet`. Every alphabet has the same
+/// characters, so a B58UUID written in another one is not an error: it
+/// decodes, to a different UUID.
+pub fn decode_with(b58: &str, alphabet: Alphabet) -> Result<Uuid, B58Error> {
+    if b58.is_empty() {
+        return Err(B58Error::EmptyBase58);
+    }
+    let zero = alphabet.digits()[0] as char;
+    let mut value = 0u128;
+    for (index, ch) in b58.chars().enumerate() {
+        if !ch.is_ascii() {
+            return Err(B58Error::NonAsciiBase58 { ch, index });
+        }
+        let digit = alphabet
+            .digits()
+            .iter()
+            .position(|&digit| digit as char == ch)
+            .ok_or(B58Error::InvalidBase58Character { ch, index })?;
+        value = value
+            .checked_mul(58)
+            .and_then(|value| value.checked_add(digit as u128))
+            .ok_or(B58Error::Overflow)?;
+    }
+    // The codec's limit: a B58UUID is at most 22 digits when padded
+    if b58.chars().take_while(|&ch| ch == zero).count() > 22 {
+        return Err(B58Error::TooManyLeadingOnes);
+    }
+    Ok(Uuid::from_u128(value))
+}
+
+/// The UUID inside `{...}` braces (the Microsoft registry form) or after a
+/// `urn:uuid:` prefix (the URN form of RFC 9562), or `uuid` itself.
+pub fn unwrap_uuid(uuid: &str) -> &str {
+    const URN: &str = "urn:uuid:";
+    if let Some(inner) = uuid
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+    {
+        return inner;
+    }
+    match uuid.get(..URN.len()) {
+        Some(prefix) if prefix.eq_ignore_ascii_case(URN) => &uuid[URN.len()..],
+        _ => uuid,
+    }
+}
+
+/// The 22-character B58UUID of a UUID string: hyphenated, 32 bare hex
+/// digits, braced or a `urn:uuid:` URN. Padded like [`encode`]. Hyphens are
+/// dropped wherever they are; [`check_hyphens`] is the strict check.
+pub fn encode_uuid(uuid: &str) -> Result<String, B58Error> {
+    Ok(encode(parse_uuid(uuid)?))
+}
+
+/// The UUID of a UUID string [`encode_uuid`] accepts. Non-ASCII input is
+/// rejected up front: the codec slices strings by byte and would panic on a
+/// multibyte character.
+fn parse_uuid(uuid: &str) -> Result<Uuid, B58Error> {
+    let uuid = ascii_uuid(uuid)?;
+    let hex: Vec<u8> = uuid.bytes().filter(|&ch| ch != b'-').collect();
+    if hex.len() != 32 {
+        return Err(B58Error::WrongLength {
+            expected: 32,
+            got: hex.len(),
+        });
+    }
+    hex.iter()
+        .enumerate()
+        .try_fold(0u128, |value, (index, &ch)| {
+            let digit = (ch as char).to_digit(16).ok_or(B58Error::InvalidHex {
+                index: index / 2 * 2,
+            })?;
+            Ok(value << 4 | digit as u128)
+        })
+        .map(Uuid::from_u128)
+}
+
+/// `uuid` unwrapped, if it is all ASCII.
+fn ascii_uuid(uuid: &str) -> Result<&str, B58Error> {
+    let uuid = unwrap_uuid(uuid);
+    match uuid.chars().enumerate().find(|(_, ch)| !ch.is_ascii()) {
+        Some((index, ch)) => Err(B58Error::NonAsciiUuid { ch, index }),
+        None => Ok(uuid),
+    }
+}
+
+/// Fails with [`B58Error::MisplacedHyphens`] unless the hyphens of `uuid`
+/// (after any braces or URN prefix) are exactly those of the 8-4-4-4-12
+/// form, or there are none. [`encode_uuid`] drops hyphens wherever they
+/// are, which silently accepts mangled exports. Non-ASCII input fails as it
+/// does there.
+pub fn check_hyphens(uuid: &str) -> Result<(), B58Error> {
+    let uuid = ascii_uuid(uuid)?;
+    let hyphens = uuid.match_indices('-').map(|(index, _)| index);
+    if uuid.contains('-') && (uuid.len() != 36 || !hyphens.eq([8, 13, 18, 23])) {
+        return Err(B58Error::MisplacedHyphens);
+    }
+    Ok(())
+}
+
+/// [`encode_uuid`] in `alphabet`.
+pub fn encode_uuid_with(uuid: &str, alphabet: Alphabet) -> Result<String, B58Error> {
+    translate(&encode_uuid(uuid)?, Alphabet::Bitcoin, alphabet)
+}
+
+/// The hyphenated UUID string of a B58UUID, padded or trimmed.
+pub fn decode_to_uuid(b58: &str) -> Result<String, B58Error> {
+    decode(b58).map(|uuid| uuid.to_string())
+}
+
+/// [`decode_to_uuid`] for a B58UUID in `alphabet`.
+pub fn decode_to_uuid_with(b58: &str, alphabet: Alphabet) -> Result<String, B58Error> {
+    decode_with(b58, alphabet).map(|uuid| uuid.to_string())
+}
+
+/// [`encode_uuid`] of every UUID string in `uuids`, in order: the result at
+/// each index is that input's B58UUID or why it failed, so one bad value
+/// neither stops the batch nor shifts the results after it.
+pub fn encode_many(uuids: &[&str]) -> Vec<Result<String, B58Error>> {
+    uuids.iter().map(|uuid| encode_uuid(uuid)).collect()
+}
+
+/// [`decode_to_uuid`] of every B58UUID in `b58s`, aligned by index like
+/// [`encode_many`].
+pub fn decode_many(b58s: &[&str]) -> Vec<Result<String, B58Error>> {
+    b58s.iter().map(|b58| decode_to_uuid(b58)).collect()
+}
+
+/// Whether `b58` is a padded 22-character B58UUID (in the Bitcoin alphabet)
+/// of a 128-bit value. Unlike [`decode`], this rejects the trimmed form, and
+/// it allocates nothing, so it suits cheap pre-checks of input.
+pub fn is_valid_b58uuid(b58: &str) -> bool {
+    is_valid_b58uuid_with(b58, Alphabet::Bitcoin)
+}
+
+/// [`is_valid_b58uuid`] for a B58UUID in `alphabet`.
+pub fn is_valid_b58uuid_with(b58: &str, alphabet: Alphabet) -> bool {
+    let digits = alphabet.digits();
+    b58.len() == 22
+        && b58
+            .bytes()
+            .try_fold(0u128, |total, ch| {
+                let digit = digits.iter().position(|&d| d == ch)?;
+                total.checked_mul(58)?.checked_add(digit as u128)
+            })
+            .is_some()
+}
+
+/// Whether `uuid` is a UUID string [`encode_uuid`] accepts, with its hyphens
+/// (if any) in the 8-4-4-4-12 places. Allocates nothing.
+pub fn is_valid_uuid(uuid: &str) -> bool {
+    let uuid = unwrap_uuid(uuid).as_bytes();
+    match uuid.len() {
+        32 => uuid.iter().all(u8::is_ascii_hexdigit),
+        36 => uuid.iter().enumerate().all(|(index, ch)| match index {
+            8 | 13 | 18 | 23 => *ch == b'-',
+            _ => ch.is_ascii_hexdigit(),
+        }),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UUID: &str = "550e8400-e29b-41d4-a716-446655440000";
+    const B58: &str = "BWBeN28Vb7cMEx7Ym8AUzs";
+
+    #[test]
+    fn non_ascii_uuid_is_rejected_before_the_codec() {
+        let error = encode_uuid("aäääääääääääääääa").unwrap_err();
+        assert_eq!(error, B58Error::NonAsciiUuid { ch: 'ä', index: 1 });
+        let error = encode_uuid_with("５５0e8400-e29b-41d4-a716-446655440000", Alphabet::Flickr);
+        assert_eq!(
+            error,
+            Err(B58Error::NonAsciiUuid {
+                ch: '５', index: 0
+            })
+        );
+    }
+
+    #[test]
+    fn uuid_errors_are_typed() {
+        assert_eq!(
+            encode_uuid("550e8400"),
+            Err(B58Error::WrongLength {
+                expected: 32,
+                got: 8
+            })
+        );
+        assert_eq!(
+            encode_uuid("550e8400-e29b-41d4-a716-44665544000g"),
+            Err(B58Error::InvalidHex { index: 30 })
+        );
+        assert_eq!(
+            check_hyphens("550e84-00e29b-41d4-a716-446655440000"),
+            Err(B58Error::MisplacedHyphens)
+        );
+        assert_eq!(check_hyphens(UUID), Ok(()));
+        assert_eq!(check_hyphens(&UUID.replace('-', "")), Ok(()));
+    }
+
+    #[test]
+    fn base58_errors_are_typed() {
+        assert_eq!(decode(""), Err(B58Error::EmptyBase58));
+        assert_eq!(
+            decode("BWBeN28Vb7cMEx7Ym8AUz0"),
+            Err(B58Error::InvalidBase58Character { ch: '0', index: 21 })
+        );
+        assert_eq!(
+            decode("BWBеN28Vb7cMEx7Ym8AUzs"),
+            Err(B58Error::NonAsciiBase58 { ch: 'е', index: 3 })
+        );
+        assert_eq!(decode(&"1".repeat(23)), Err(B58Error::TooManyLeadingOnes));
+        assert_eq!(decode(&"z".repeat(22)), Err(B58Error::Overflow));
+    }
+
+    #[test]
+    fn round_trips_in_every_alphabet() {
+        assert_eq!(encode_uuid(UUID).unwrap(), B58);
+        assert_eq!(decode_to_uuid(B58).unwrap(), UUID);
+        for alphabet in Alphabet::ALL {
+            let b58 = encode_uuid_with(UUID, alphabet).unwrap();
+            assert_eq!(decode_to_uuid_with(&b58, alphabet).unwrap(), UUID);
+        }
+        assert_eq!(decode(&"1".repeat(22)).unwrap(), Uuid::nil());
+    }
+
+    #[test]
+    fn encodings_are_always_22_characters() {
+        for uuid in [
+            "00000000-0000-0000-0000-000000000000",
+            "00000000-0000-0000-0000-000000000001",
+            "000000ff-ffff-ffff-ffff-ffffffffffff",
+            "00ffffff-ffff-ffff-ffff-ffffffffffff",
+            "ffffffff-ffff-ffff-ffff-ffffffffffff",
+        ] {
+            for alphabet in Alphabet::ALL {
+                let b58 = encode_uuid_with(uuid, alphabet).unwrap();
+                assert_eq!(b58.len(), 22, "{} in {:?}: {}", uuid, alphabet, b58);
+                assert_eq!(decode_to_uuid_with(&b58, alphabet).unwrap(), uuid);
+            }
+        }
+        assert_eq!(
+            encode_uuid("000000ff-ffff-ffff-ffff-ffffffffffff").unwrap(),
+            "1111NKioeUVktgzXLJ1B3t"
+        );
+        assert_eq!(
+            encode_uuid("00000000-0000-0000-0000-000000000000").unwrap(),
+            "1".repeat(22)
+        );
+    }
+
+    #[test]
+    fn valid_b58uuids_are_22_digits_of_a_128_bit_value() {
+        assert!(is_valid_b58uuid(B58));
+        assert!(is_valid_b58uuid(&"1".repeat(22)));
+        assert!(is_valid_b58uuid("YcVfxkQb6JRzqk5kF2tNLv"));
+        for invalid in [
+            "",
+            "2",
+            "BWBeN28Vb7cMEx7Ym8AUz",
+            "1BWBeN28Vb7cMEx7Ym8AUzs",
+            "BWBeN28Vb7cMEx7Ym8AUz0",
+            "BWBeN28Vb7cMEx7Ym8AUzl",
+            "BWBеN28Vb7cMEx7Ym8AUzs",
+            "zzzzzzzzzzzzzzzzzzzzzz",
+        ] {
+            assert!(!is_valid_b58uuid(invalid), "{:?}", invalid);
+        }
+        let ripple = encode_uuid_with(UUID, Alphabet::Ripple).unwrap();
+        assert!(is_valid_b58uuid_with(&ripple, Alphabet::Ripple));
+    }
+
+    #[test]
+    fn valid_uuids_have_hyphens_in_place_or_none() {
+        for valid in [
+            UUID,
+            "550E8400-E29B-41D4-A716-446655440000",
+            "550e8400e29b41d4a716446655440000",
+            "{550e8400-e29b-41d4-a716-446655440000}",
+            "urn:uuid:550e8400-e29b-41d4-a716-446655440000",
+        ] {
+            assert!(is_valid_uuid(valid), "{:?}", valid);
+        }
+        for invalid in [
+            "2",
+            "550e84-00e29b-41d4-a716-446655440000",
+            "550e8400-e29b-41d4-a716-44665544000",
+            "550e8400-e29b-41d4-a716-44665544000g",
+            "５50e8400-e29b-41d4-a716-446655440000",
+            "550e8400-e29b-41d4-a716-4466554400000",
+        ] {
+            assert!(!is_valid_uuid(invalid), "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn non_canonical_forms_encode_like_the_canonical_one() {
+        for form in [
+            "{550e8400-e29b-41d4-a716-446655440000}",
+            "urn:uuid:550e8400-e29b-41d4-a716-446655440000",
+            "URN:UUID:550e8400-e29b-41d4-a716-446655440000",
+            "550e8400e29b41d4a716446655440000",
+            "550E8400-E29B-41D4-A716-446655440000",
+        ] {
+            assert_eq!(encode_uuid(form).unwrap(), B58, "{}", form);
+            assert_eq!(check_hyphens(form), Ok(()), "{}", form);
+        }
+    }
+
+    #[test]
+    fn malformed_forms_still_fail() {
+        assert_eq!(
+            check_hyphens("{550e8400e29b-41d4-a716-446655440000}"),
+            Err(B58Error::MisplacedHyphens)
+        );
+        assert_eq!(
+            check_hyphens("urn:uuid:550e-8400e29b41d4a716446655440000"),
+            Err(B58Error::MisplacedHyphens)
+        );
+        for malformed in [
+            "{550e8400-e29b-41d4-a716-446655440000",
+            "550e8400-e29b-41d4-a716-446655440000}",
+            "urn:uuid:",
+            "uuid:550e8400-e29b-41d4-a716-446655440000",
+            "{{550e8400-e29b-41d4-a716-446655440000}}",
+        ] {
+            assert!(encode_uuid(malformed).is_err(), "{}", malformed);
+        }
+    }
+
+    #[test]
+    fn batches_keep_each_result_at_its_input_index() {
+        let results = encode_many(&[UUID, "550e8400", "", UUID, "not-a-uuid"]);
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].as_deref(), Ok(B58));
+        assert_eq!(
+            results[1],
+            Err(B58Error::WrongLength {
+                expected: 32,
+                got: 8
+            })
+        );
+        assert!(results[2].is_err());
+        assert_eq!(results[3].as_deref(), Ok(B58));
+        assert!(results[4].is_err());
+
+        let results = decode_many(&["BWBeN28Vb7cMEx7Ym8AUz0", B58, "", B58]);
+        assert_eq!(
+            results,
+            [
+                Err(B58Error::InvalidBase58Character { ch: '0', index: 21 }),
+                Ok(UUID.to_string()),
+                Err(B58Error::EmptyBase58),
+                Ok(UUID.to_string()),
+            ]
+        );
+        assert!(encode_many(&[]).is_empty());
+    }
+
+    #[test]
+    fn short_legacy_values_decode_to_the_padded_uuid() {
+        for (legacy, uuid) in [
+            (
+                "88n9YPzi1DJSS6unA2P7u",
+                "00ff0000-0000-0000-0000-000000000000",
+            ),
+            ("NKioeUVktgzXLJ1B3t", "000000ff-ffff-ffff-ffff-ffffffffffff"),
+            ("2", "00000000-0000-0000-0000-000000000001"),
+            ("1", "00000000-0000-0000-0000-000000000000"),
+        ] {
+            assert_eq!(decode_to_uuid(legacy).unwrap(), uuid, "{}", legacy);
+            let padded = encode_uuid(uuid).unwrap();
+            assert_eq!(
+                padded.trim_start_matches('1'),
+                legacy.trim_start_matches('1')
+            );
+            assert!(!is_valid_b58uuid(legacy), "{}", legacy);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Diagnostics printed under `-v`, sent to stderr or (with the `syslog`
+//! feature, on Unix) the system logger for runs under a service manager
+//! that does not capture stderr.
+
+use anyhow::Result;
+use clap::ValueEnum;
+use std::sync::Mutex;
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LogTarget {
+    /// Standard error
+    Stderr,
+    /// The local syslog daemon (or journald's syslog socket), facility user
+    Syslog,
+}
+
+enum Sink {
+    Stderr,
+    #[cfg(all(unix, feature = "syslog"))]
+    Syslog(syslog::Logger<syslog::LoggerBackend, syslog::Formatter3164>),
+}
+
+/// Where `verbose` writes; `None` when `-v` was not given.
+static SINK: Mutex<Option<Sink>> = Mutex::new(None);
+
+/// Turn on `-v` diagnostics, written to `target`.
+pub fn init(target: LogTarget) -> Result<()> {
+    let sink = match target {
+        LogTarget::Stderr => Sink::Stderr,
+        LogTarget::Syslog => syslog_sink()?,
+    };
+    *SINK.lock().unwrap_or_else(|e| e.into_inner()) = Some(sink);
+    Ok(())
+}
+
+#[cfg(all(unix, feature = "syslog"))]
+fn syslog_sink() -> Result<Sink> {
+    let formatter = syslog::Formatter3164 {
+        facility: syslog::Facility::LOG_USER,
+        hostname: None,
+        process: "b58uuid".to_string(),
+        pid: std::process::id(),
+    };
+    let logger = syslog::unix(formatter)
+        .map_err(|e| anyhow::anyhow!("Failed to connect to syslog: {}", e))?;
+    Ok(Sink::Syslog(logger))
+}
+
+#[cfg(not(all(unix, feature = "syslog")))]
+fn syslog_sink() -> Result<Sink> {
+    anyhow::bail!("--log-target syslog needs a Unix build with the `syslog` feature")
+}
+
+/// Write one `-v` diagnostic line, if `-v` was given.
+pub fn verbose(message: &str) {
+    let mut sink = SINK.lock().unwrap_or_else(|e| e.into_inner());
+    match sink.as_mut() {
+        None => {}
+        Some(Sink::Stderr) => eprintln!("{}", message),
+        #[cfg(all(unix, feature = "syslog"))]
+        Some(Sink::Syslog(logger)) => {
+            // Losing a diagnostic is better than failing the run over it
+            let _ = logger.info(message);
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//! `map`: a lookup table from every value of the input to its conversion,
+//! keeping both columns so the table can be joined against the original
+//! dataset.
+//!
+//! Unlike a `--map-format` document, the table has no header and one
+//! `original<TAB>converted` line per value, which `join`, spreadsheets and
+//! database bulk loaders read as is; `--format csv` or `json` write the same
+//! two fields as records.
+
+use anyhow::Result;
+use clap::ValueEnum;
+use colored::*;
+use std::collections::HashSet;
+
+use crate::input::Lines;
+use crate::output::{OutputWriter, Record};
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MapDirection {
+    /// UUIDs to B58UUIDs
+    Encode,
+    /// B58UUIDs to UUIDs
+    Decode,
+}
+
+/// Write one record per line of `lines` pairing the value with its
+/// conversion (only its first occurrence with `unique`). Invalid values are
+/// left out of the table and reported on stderr, followed by the totals.
+pub fn map(
+    lines: Lines,
+    direction: MapDirection,
+    unique: bool,
+    out: &mut dyn OutputWriter,
+) -> Result<()> {
+    let mut seen = HashSet::new();
+    let (mut mapped, mut invalid) = (0, 0);
+    for line in lines {
+        let line = line?;
+        let value = crate::input::trim(&line);
+        if value.is_empty() || (unique && !seen.insert(value.to_string())) {
+            continue;
+        }
+        let converted = match direction {
+            MapDirection::Encode => crate::alphabet::encode_uuid(value),
+            MapDirection::Decode => crate::alphabet::decode_to_uuid(value),
+        };
+        match converted {
+            Ok(converted) => {
+                mapped += 1;
+                out.record(
+                    Record::new(format!("{}\t{}", value, converted))
+                        .field("original", value)
+                        .field("converted", converted),
+                )?;
+            }
+            Err(e) => {
+                invalid += 1;
+                if !crate::errors::quiet() {
+                    eprintln!(
+                        "{} {} - {} (skipped)",
+                        "Error:".red().bold(),
+                        value,
+                        crate::errors::describe(&e)
+                    );
+                }
+            }
+        }
+    }
+    out.flush()?;
+    eprintln!("Mapped {} values: {} invalid skipped", mapped, invalid);
+    Ok(())
+}
@@ -1,7 +1,56 @@
-use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use anyhow::{bail, Context, Result};
+use b58uuid_cli::B58Error;
+use clap::builder::TypedValueParser;
+use clap::{Args, CommandFactory, Parser, Subcommand};
 use colored::*;
-use std::io::{self, BufRead};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use unicode_normalization::UnicodeNormalization;
+
+use input::{
+    ColumnRange, InputFormat, InputOptions, InputSource, Lines, SourcedLines, WhereFilter,
+};
+use output::{Format, LineEnding, OutputOptions, OutputWriter, Record};
+use serde_json::Value;
+
+mod alphabet;
+mod bench;
+mod byteorder;
+mod check;
+mod count;
+mod dedupe;
+mod derive;
+mod diff;
+mod encoding;
+mod errors;
+mod fingerprint;
+mod generate;
+mod input;
+mod inspect;
+mod length;
+mod log;
+mod lookup;
+mod mapping;
+mod meter;
+mod number;
+mod output;
+mod prefixmap;
+mod profile;
+mod rawbytes;
+mod resume;
+mod rewrite;
+mod selective;
+mod sort;
+mod sortkey;
+mod spec;
+mod timestamp;
+mod trace;
+mod verify;
 
 #[derive(Parser)]
 #[command(name = "b58uuid")]
@@ -23,13 +72,120 @@ struct Cli {
     #[arg(
         long,
         global = true,
-        help = "Disable colored output for piping or logging"
+        conflicts_with = "color",
+        help = "Same as --color never (deprecated)"
     )]
     no_color: bool,
 
-    /// Output format (currently only 'text' is supported)
-    #[arg(short, long, global = true, default_value = "text", hide = true)]
-    format: String,
+    /// When to color output: only when stdout is a terminal and NO_COLOR is
+    /// not set (auto), always (even through pipes), or never
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "WHEN",
+        default_value = "auto"
+    )]
+    color: ColorChoice,
+
+    /// Print diagnostic details (such as the random source) to stderr
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    /// Where -v diagnostics go; syslog needs a Unix build with the `syslog`
+    /// feature
+    #[arg(long, global = true, value_enum, default_value = "stderr")]
+    log_target: log::LogTarget,
+
+    /// Print a summary of processed lines to stderr after batch runs
+    #[arg(long, visible_alias = "summary", global = true)]
+    stats: bool,
+
+    /// Do not print the errors of individual lines in batch runs (a single
+    /// value still reports its error); --stats still counts them
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// After encode/decode, print to stderr how long was spent reading,
+    /// converting and writing
+    #[arg(long, global = true)]
+    profile: bool,
+
+    /// Output format
+    #[arg(long, global = true, value_enum, default_value = "text")]
+    format: Format,
+
+    /// Input format for --file/stdin; auto picks from the file extension
+    #[arg(long, global = true, value_enum, default_value = "auto")]
+    input_format: InputFormat,
+
+    /// JSON key or CSV column holding the value (default: the first one)
+    #[arg(long, global = true, value_name = "NAME")]
+    input_field: Option<String>,
+
+    /// Split text input on any whitespace, not just line breaks, so
+    /// several values can share a line; each token is one value
+    #[arg(long, global = true)]
+    whitespace: bool,
+
+    /// Skip text lines starting with '#' (after leading whitespace) in stdin
+    /// too; they are always skipped in --file input
+    #[arg(long, global = true, conflicts_with = "no_comments")]
+    comments: bool,
+
+    /// Treat '#' lines of --file input as values like any other
+    #[arg(long, global = true)]
+    no_comments: bool,
+
+    /// Also strip these characters from both ends of each value converted
+    /// or validated, e.g. '"\',' for quoted, comma-terminated exports
+    #[arg(long, global = true, value_name = "SET")]
+    trim_chars: Option<String>,
+
+    /// Line ending for all output, including files written with --output
+    #[arg(long, global = true, value_enum, default_value = "lf")]
+    line_ending: LineEnding,
+
+    /// Split text input on NUL bytes instead of line breaks and end each
+    /// output record with NUL, for find -print0 and xargs -0 (text, ndjson
+    /// and csv output)
+    #[arg(
+        short = '0',
+        long,
+        global = true,
+        conflicts_with_all = ["whitespace", "line_ending"]
+    )]
+    null: bool,
+
+    /// Flush after every record (text, ndjson, csv) so live consumers see
+    /// each one at once, at some cost in throughput
+    #[arg(long, global = true)]
+    flush: bool,
+
+    /// Bytes of output collected before each write to stdout or an --output
+    /// file (default: stdout line by line, files 65536)
+    #[arg(
+        long,
+        global = true,
+        value_name = "BYTES",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    buffer_size: Option<u64>,
+
+    /// How failed values are reported: descriptive messages, or stable codes
+    /// for tests and log matching
+    #[arg(long, global = true, value_enum, default_value = "human")]
+    error_format: errors::ErrorFormat,
+
+    /// Base58 alphabet of the B58UUIDs read and written: bitcoin (the
+    /// standard), ripple or flickr. A B58UUID that decodes to a standard
+    /// (RFC 9562) UUID only in another alphabet is rejected
+    #[arg(long, global = true, default_value = "bitcoin", value_parser = alphabet::parser())]
+    alphabet: b58uuid_cli::Alphabet,
+
+    /// Overwrite existing output files without asking
+    #[arg(short, long, global = true)]
+    yes: bool,
 }
 
 #[derive(Subcommand)]
@@ -39,14 +195,102 @@ enum Commands {
     #[command(after_help = "EXAMPLES:\n  \
         b58uuid encode 550e8400-e29b-41d4-a716-446655440000\n  \
         echo 'uuid...' | b58uuid enc\n  \
-        b58uuid encode --file uuids.txt")]
+        b58uuid encode --file uuids.txt\n  \
+        b58uuid encode --file uuids.txt --exclude-version 1 --stats\n  \
+        b58uuid encode --file uuids.txt --baseline old.txt --changed-only\n  \
+        b58uuid encode 550e8400-e29b-41d4-a716-446655440000 --annotate-savings\n  \
+        b58uuid encode --tar dump.tar.gz --with-source\n  \
+        b58uuid encode --file uuids.txt --map-format json -o mapping.json\n  \
+        b58uuid encode --file export.dat --columns 10:46 --keep-layout\n  \
+        b58uuid encode 550e8400-e29b-41d4-a716-446655440000 --check\n  \
+        b58uuid encode 550e8400e29b-41d4a716446655440000 --tolerant-hyphens\n  \
+        db-export-uuid | b58uuid encode --from-bytes\n  \
+        tail -f app.log | b58uuid encode --inline")]
     Encode {
         /// UUID to encode (or read from stdin)
+        #[arg(id = "value", value_name = "UUID")]
         uuid: Option<String>,
 
-        /// Read UUIDs from file (one per line)
+        /// Read UUIDs from file (one per line); repeat to read several files
+        /// in turn
         #[arg(short, long, value_name = "FILE")]
-        file: Option<String>,
+        file: Vec<String>,
+
+        #[command(flatten)]
+        batch: BatchArgs,
+
+        /// Append a 4-byte checksum (Base58Check), giving a 28-character
+        /// value that `validate --check` can verify
+        #[arg(long, conflicts_with = "annotate_savings")]
+        check: bool,
+
+        /// Append the bytes saved by each B58UUID (vs the UUID string and the
+        /// 16-byte binary form); structured formats get fields instead
+        #[arg(long)]
+        annotate_savings: bool,
+
+        /// Accept UUIDs with hyphens in the wrong places by ignoring them;
+        /// the rest must still be 32 hex digits
+        #[arg(long)]
+        tolerant_hyphens: bool,
+
+        /// Apply Unicode NFKC normalization before parsing, folding
+        /// fullwidth digits and letters (e.g. '５５０ｅ') to ASCII
+        #[arg(long)]
+        unicode_normalize: bool,
+
+        /// How the 16 UUID bytes map to the Base58 integer: RFC 4122 order
+        /// (network, big-endian) or Microsoft GUID order (mixed)
+        #[arg(long, value_enum, default_value = "network")]
+        byte_order: byteorder::ByteOrder,
+
+        /// Write each B58UUID to DIR/<first character>.txt instead of
+        /// stdout, pre-sharding the output; per-file counts go to stderr
+        #[arg(
+            long,
+            value_name = "DIR",
+            conflicts_with_all = ["value", "output", "map_format", "where_filter", "csv", "serve"]
+        )]
+        prefix_map: Option<PathBuf>,
+
+        /// Keep the leading '1's (Base58 zero digits) that pad every B58UUID
+        /// to 22 characters, or trim them for the shortest form; decode
+        /// accepts both
+        #[arg(
+            long,
+            value_enum,
+            default_value = "pad",
+            value_name = "MODE",
+            conflicts_with = "check"
+        )]
+        zero_handling: ZeroHandling,
+
+        /// Rewrite every UUID found in each line to its B58UUID in place,
+        /// passing the rest of the line through unchanged (as url-rewrite)
+        #[arg(
+            long,
+            conflicts_with_all = [
+                "check", "annotate_savings", "tolerant_hyphens", "unicode_normalize",
+                "byte_order", "prefix_map", "zero_handling", "exclude_version", "baseline",
+                "resume", "error_summary", "jobs", "tar", "with_source", "map_format",
+                "columns", "sort", "unique", "dedupe_window", "where_filter", "csv", "serve"
+            ]
+        )]
+        inline: bool,
+
+        /// Read the UUID as its 16 bytes: VALUE as 32 hex digits, or the
+        /// whole of --file/stdin as 16 raw bytes or hex
+        #[arg(
+            long,
+            conflicts_with_all = [
+                "check", "annotate_savings", "tolerant_hyphens", "unicode_normalize",
+                "byte_order", "prefix_map", "zero_handling", "exclude_version", "baseline",
+                "resume", "error_summary", "jobs", "tar", "with_source", "map_format",
+                "columns", "sort", "unique", "dedupe_window", "where_filter", "csv", "serve",
+                "inline"
+            ]
+        )]
+        from_bytes: bool,
     },
 
     /// Decode B58UUID to UUID (22 → 36 chars) [alias: dec]
@@ -54,14 +298,119 @@ enum Commands {
     #[command(after_help = "EXAMPLES:\n  \
         b58uuid decode BWBeN28Vb7cMEx7Ym8AUzs\n  \
         echo 'b58uuid...' | b58uuid dec\n  \
-        b58uuid decode --file b58uuids.txt")]
+        b58uuid decode --file b58uuids.txt\n  \
+        b58uuid decode --file b58uuids.txt --error-summary\n  \
+        b58uuid decode BWBeN28Vb7cMEx7Ym8AUzs --all-forms\n  \
+        b58uuid decode --file b58uuids.txt --uppercase\n  \
+        b58uuid decode BWBeN28Vb7cMEx7Ym8AUzs --to-bytes\n  \
+        b58uuid decode --inline --file app.b58.log")]
     Decode {
         /// B58UUID to decode (or read from stdin)
+        #[arg(id = "value", value_name = "B58UUID")]
         b58uuid: Option<String>,
 
-        /// Read B58UUIDs from file (one per line)
+        /// Read B58UUIDs from file (one per line); repeat to read several
+        /// files in turn
         #[arg(short, long, value_name = "FILE")]
-        file: Option<String>,
+        file: Vec<String>,
+
+        #[command(flatten)]
+        batch: BatchArgs,
+
+        /// Print every form of the UUID: canonical, uppercase, braced, hex
+        /// and integer
+        #[arg(long)]
+        all_forms: bool,
+
+        /// Print UUIDs in uppercase hex
+        #[arg(long, conflicts_with_all = ["lowercase", "all_forms"])]
+        uppercase: bool,
+
+        /// Print UUIDs in lowercase hex (the default)
+        #[arg(long, conflicts_with = "all_forms")]
+        lowercase: bool,
+
+        /// Print the 16 UUID bytes as 32 hex digits instead of the UUID
+        /// string
+        #[arg(long, conflicts_with_all = ["all_forms", "inline"])]
+        to_bytes: bool,
+
+        /// Print UUIDs as `urn:uuid:` URNs (RFC 9562), e.g. for XML and RDF
+        #[arg(long, conflicts_with_all = ["all_forms", "to_bytes", "inline"])]
+        urn: bool,
+
+        /// Reject B58UUIDs that are not 22 characters (trimmed forms) or
+        /// whose letters are all one case, as a case-folded value would be,
+        /// instead of decoding them with a warning
+        #[arg(long, conflicts_with = "inline")]
+        strict_b58: bool,
+
+        /// Reject B58UUIDs that are not 22 characters instead of decoding
+        /// the trimmed form of older encoders (implied by --strict-b58)
+        #[arg(long, conflicts_with = "inline")]
+        strict_length: bool,
+
+        /// How the 16 UUID bytes map to the Base58 integer: RFC 4122 order
+        /// (network, big-endian) or Microsoft GUID order (mixed)
+        #[arg(long, value_enum, default_value = "network")]
+        byte_order: byteorder::ByteOrder,
+
+        /// Rewrite every B58UUID (any 22-character Base58 word) found in each
+        /// line to its UUID in place, passing the rest of the line through
+        #[arg(
+            long,
+            conflicts_with_all = [
+                "all_forms", "byte_order", "exclude_version", "baseline", "resume",
+                "error_summary", "jobs", "tar", "with_source", "map_format", "columns",
+                "sort", "unique", "dedupe_window", "where_filter", "csv", "serve"
+            ]
+        )]
+        inline: bool,
+    },
+
+    /// Convert each value to the other form, detecting which it is [alias: tc]
+    #[command(alias = "tc")]
+    #[command(after_help = "EXAMPLES:\n  \
+        b58uuid transcode BWBeN28Vb7cMEx7Ym8AUzs\n  \
+        b58uuid tc --file mixed.txt\n  \
+        b58uuid tc --file mixed.txt --to b58uuid\n  \
+        b58uuid tc BWBeN28Vb7cMEx7Ym8AUzs --output-encoding base62\n  \
+        b58uuid tc --file ids.b62 --input-encoding base62 --output-encoding base58")]
+    Transcode {
+        /// UUID or B58UUID to convert (or read from stdin)
+        #[arg(id = "value", value_name = "VALUE")]
+        value: Option<String>,
+
+        /// Read values from file (one per line); repeat to read several files
+        /// in turn
+        #[arg(short, long, value_name = "FILE")]
+        file: Vec<String>,
+
+        #[command(flatten)]
+        batch: BatchArgs,
+
+        /// Convert every value to this form, normalizing values already in
+        /// it, instead of to the other one
+        #[arg(long, value_enum, value_name = "FORM")]
+        to: Option<Form>,
+
+        /// Encoding every value is read in, instead of detecting UUID or
+        /// B58UUID
+        #[arg(
+            long,
+            value_enum,
+            value_name = "ENCODING",
+            requires = "output_encoding"
+        )]
+        input_encoding: Option<encoding::Encoding>,
+
+        /// Encoding to write every value in, converting the 16 bytes directly
+        #[arg(long, value_enum, value_name = "ENCODING", conflicts_with = "to")]
+        output_encoding: Option<encoding::Encoding>,
+
+        /// Write the values converted to UUIDs as `urn:uuid:` URNs
+        #[arg(long)]
+        urn: bool,
     },
 
     /// Generate random B58UUID or UUID [alias: gen]
@@ -69,190 +418,3065 @@ enum Commands {
     #[command(after_help = "EXAMPLES:\n  \
         b58uuid generate\n  \
         b58uuid gen -n 5\n  \
-        b58uuid gen --uuid")]
+        b58uuid gen --uuid\n  \
+        b58uuid gen -n 1000 --version 7\n  \
+        b58uuid gen --version 5 --namespace dns --name example.com\n  \
+        b58uuid gen --version 5 --namespace url --file urls.txt\n  \
+        b58uuid gen -n 1000 -o ids.txt --atomic\n  \
+        b58uuid gen -n 1000000 --shards 10 --shard-prefix part_\n  \
+        b58uuid gen -n 5 --seed 42 -v\n  \
+        b58uuid gen -n 5 --seed-file team.seed\n  \
+        b58uuid gen --interval 500 -n 0 | consumer\n  \
+        b58uuid gen --infinite | head -n 1000000 > load.txt\n  \
+        b58uuid gen --duration 60 --rate 1000 -o soak.txt\n  \
+        b58uuid gen -n 3 --match '[0-9]{3}$' --stats\n  \
+        b58uuid gen --spec fixtures.json -o fixtures.txt")]
     Generate {
-        /// Number of UUIDs to generate
-        #[arg(short = 'n', long, default_value = "1", value_name = "COUNT")]
-        count: usize,
+        /// Read options from a JSON object keyed by flag name (e.g.
+        /// {"count": 100, "version": 7}); flags given here override it
+        #[arg(long, value_name = "FILE")]
+        spec: Option<String>,
+
+        /// Number of UUIDs to generate [default: 1]
+        #[arg(short = 'n', long, value_name = "COUNT")]
+        count: Option<usize>,
 
         /// Generate as standard UUID (36 chars) instead of B58UUID (22 chars)
         #[arg(short, long)]
         uuid: bool,
+
+        /// Print each value as `<uuid><TAB><b58uuid>`, for loading mapping
+        /// tables
+        #[arg(long, conflicts_with = "uuid")]
+        both: bool,
+
+        /// Separator of the two forms with --both [default: a tab]
+        #[arg(long, value_name = "STR")]
+        delimiter: Option<String>,
+
+        /// Write generated values to a file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<String>,
+
+        /// Write to a temporary file and rename it into place only on success
+        /// (requires --output)
+        #[arg(long)]
+        atomic: bool,
+
+        /// Split the values across this many files, generated in parallel
+        #[arg(
+            long,
+            value_name = "M",
+            conflicts_with = "output",
+            value_parser = clap::value_parser!(u32).range(1..)
+        )]
+        shards: Option<u32>,
+
+        /// File name prefix for shards; shard i is written to <PREFIX><i>.txt
+        /// (requires --shards) [default: shard_]
+        #[arg(long, value_name = "PREFIX")]
+        shard_prefix: Option<String>,
+
+        /// Random source: the OS CSPRNG, or a seeded generator for audits
+        /// and fixtures [default: seeded with --seed/--seed-file, else os]
+        #[arg(long, value_enum)]
+        rng: Option<generate::RngKind>,
+
+        /// UUID version: random (4), named (5) or time-ordered (7); v7
+        /// values from one run are strictly increasing [default: 4]
+        #[arg(long, value_enum)]
+        version: Option<generate::UuidVersion>,
+
+        /// Namespace for --version 5: dns, url, oid, x500, or a UUID
+        #[arg(long, value_name = "NAMESPACE")]
+        namespace: Option<String>,
+
+        /// Name for --version 5, hashed with the namespace; repeat for one
+        /// UUID per name
+        #[arg(long, value_name = "STRING")]
+        name: Vec<String>,
+
+        /// Read names for --version 5 from file (one per line) when no
+        /// --name is given, else from piped stdin; repeat to read several
+        /// files in turn
+        #[arg(short, long, value_name = "FILE")]
+        file: Vec<String>,
+
+        /// Seed the generator for a reproducible sequence (implies --rng
+        /// seeded): the same seed and count give the same values everywhere
+        #[arg(long, value_name = "SEED")]
+        seed: Option<u64>,
+
+        /// Seed read from a file (implies --rng seeded): its first 32 bytes
+        /// are the ChaCha12 key, for sharing a reproducible seed artifact
+        #[arg(long, value_name = "PATH", conflicts_with = "seed")]
+        seed_file: Option<String>,
+
+        /// Emit one value every MS milliseconds, flushing each; with
+        /// --count 0 run until Ctrl-C
+        #[arg(long, value_name = "MS", conflicts_with = "shards")]
+        interval: Option<u64>,
+
+        /// Emit values until Ctrl-C or until the reader closes the pipe (as
+        /// with `| head -n N`), flushing every few hundred
+        #[arg(
+            long,
+            conflicts_with_all = ["count", "atomic", "shards", "interval", "duration"]
+        )]
+        infinite: bool,
+
+        /// Keep emitting values for SECS seconds instead of a fixed count,
+        /// then report how many were generated; Ctrl-C stops early
+        #[arg(
+            long,
+            value_name = "SECS",
+            conflicts_with_all = ["count", "shards", "interval"],
+            value_parser = generate::parse_duration
+        )]
+        duration: Option<Duration>,
+
+        /// With --duration, emit at most N values per second
+        #[arg(
+            long,
+            value_name = "N",
+            value_parser = clap::value_parser!(u32).range(1..)
+        )]
+        rate: Option<u32>,
+
+        /// Warn on stderr when a value repeats (keeps every value in memory)
+        #[arg(long)]
+        warn_duplicates: bool,
+
+        /// Only emit values whose B58UUID matches this regex, drawing random
+        /// candidates until one does; --stats reports the attempts
+        #[arg(long = "match", value_name = "REGEX", conflicts_with = "shards")]
+        pattern: Option<String>,
+
+        /// Candidates to try per value with --match before giving up
+        /// [default: 10000000]
+        #[arg(
+            long,
+            value_name = "N",
+            value_parser = clap::value_parser!(u64).range(1..)
+        )]
+        max_attempts: Option<u64>,
+    },
+
+    /// Generate a CSV of test data with a B58UUID id column
+    #[command(after_help = "EXAMPLES:\n  \
+        b58uuid gen-table -n 1000 --columns id,created_at,name\n  \
+        b58uuid gen-table -n 10 --seed 42 > fixture.csv")]
+    GenTable {
+        /// Number of rows to generate
+        #[arg(short = 'n', long, default_value = "10", value_name = "COUNT")]
+        count: usize,
+
+        /// Comma-separated column names: `id` is a B58UUID, `created_at` an
+        /// increasing timestamp, anything else gets placeholder values
+        #[arg(
+            long,
+            value_name = "NAMES",
+            value_delimiter = ',',
+            default_value = "id,created_at"
+        )]
+        columns: Vec<String>,
+
+        /// Seed the random source so the same seed yields the same table
+        #[arg(long, value_name = "SEED")]
+        seed: Option<u64>,
+    },
+
+    /// Derive a deterministic B58UUID (a v8 UUID) from a hash of any string
+    #[command(after_help = "EXAMPLES:\n  \
+        b58uuid derive user@example.com\n  \
+        b58uuid derive user@example.com --hash blake3\n\n\
+The first 16 bytes of the digest become a version 8 (custom) UUID per
+RFC 9562: 4 version bits and 2 variant bits are overwritten, the other 122
+bits come from the hash.")]
+    Derive {
+        /// Input string to hash (used as-is, without trimming)
+        input: String,
+
+        /// Hash function applied to the input
+        #[arg(long, value_enum, default_value = "sha256")]
+        hash: derive::Hash,
+    },
+
+    /// Build a v8 (custom) UUID around 16 bytes of your own data
+    #[command(after_help = "EXAMPLES:\n  \
+        b58uuid v8 0x00112233445566778899aabbccddeeff\n  \
+        b58uuid v8 --file payload.bin\n\n\
+The version nibble (bits 48-51) is set to 8 and the variant (bits 64-65) to
+0b10, per RFC 9562; the other 122 bits are your data.")]
+    V8 {
+        /// 16 bytes as 32 hex digits (whitespace and a 0x prefix are ignored)
+        #[arg(value_name = "HEX", required_unless_present = "file")]
+        hex: Option<String>,
+
+        /// Read the 16 raw bytes from a file instead
+        #[arg(short, long, value_name = "FILE", conflicts_with = "hex")]
+        file: Option<String>,
+    },
+
+    /// Replace UUIDs inside URLs or log lines with B58UUIDs, keeping the rest
+    #[command(after_help = "EXAMPLES:\n  \
+        b58uuid url-rewrite 'https://api.example.com/users/550e8400-e29b-41d4-a716-446655440000?ref=x'\n  \
+        b58uuid url-rewrite --file access.log > access.b58.log\n  \
+        b58uuid url-rewrite --decode --file access.b58.log")]
+    UrlRewrite {
+        /// Line of text to rewrite (or read lines from stdin)
+        line: Option<String>,
+
+        /// Read lines to rewrite from file
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<String>,
+
+        /// Replace B58UUIDs with UUIDs instead
+        #[arg(long)]
+        decode: bool,
+    },
+
+    /// Replace quoted UUID literals in a SQL dump with quoted B58UUIDs,
+    /// leaving every other byte of the dump as it is
+    #[command(after_help = "EXAMPLES:\n  \
+        b58uuid sql-rewrite dump.sql -o dump.b58.sql\n  \
+        pg_dump mydb | b58uuid sql-rewrite > dump.b58.sql\n  \
+        b58uuid sql-rewrite --decode dump.b58.sql -o dump.sql")]
+    SqlRewrite {
+        /// SQL dump to rewrite (or read from stdin)
+        file: Option<String>,
+
+        /// Write the rewritten dump to a file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<String>,
+
+        /// Replace quoted B58UUIDs with quoted UUIDs instead
+        #[arg(long)]
+        decode: bool,
+    },
+
+    /// Write a lookup table pairing every value of a file with its
+    /// conversion, one `original<TAB>converted` line each
+    #[command(after_help = "EXAMPLES:\n  \
+        b58uuid map --file users.uuids -o users.map.tsv\n  \
+        b58uuid map --file ids.b58 --direction decode --unique --format json -o ids.json\n\n\
+Invalid values are left out of the table and reported on stderr with the
+totals.")]
+    Map {
+        /// Read values from file instead of stdin
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<String>,
+
+        /// Write the table to a file instead of stdout
+        #[arg(short, long, visible_alias = "out", value_name = "FILE")]
+        output: Option<String>,
+
+        /// Whether the input holds UUIDs to encode or B58UUIDs to decode
+        #[arg(long, value_enum, default_value = "encode")]
+        direction: lookup::MapDirection,
+
+        /// List each input value once, at its first occurrence
+        #[arg(short, long)]
+        unique: bool,
+    },
+
+    /// Undo a migration recorded with --map-format: map each converted value
+    /// back to its original
+    #[command(after_help = "EXAMPLES:\n  \
+        b58uuid encode --file uuids.txt --map-format json -o mapping.json\n  \
+        b58uuid reverse-map mapping.json BWBeN28Vb7cMEx7Ym8AUzs\n  \
+        b58uuid reverse-map mapping.json --file b58uuids.txt")]
+    ReverseMap {
+        /// Mapping document written by --map-format json
+        map: String,
+
+        /// Value to map back (or read from stdin)
+        value: Option<String>,
+
+        /// Read values to map back from file (one per line)
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<String>,
+    },
+
+    /// Print a B58UUID that sorts by time for a v1, v6 or v7 UUID
+    #[command(after_help = "EXAMPLES:\n  \
+        b58uuid sortkey 1ec9414c-232a-6b00-b3c8-9e6bdeced846\n  \
+        b58uuid sortkey --file v1-ids.txt | sort\n\n\
+v7 and v6 UUIDs are already in time order and are encoded unchanged; v1
+UUIDs are first rewritten in the v6 layout, so v1 and v6 keys sort together.
+v7 keys use a different clock and only sort against other v7 keys.")]
+    Sortkey {
+        /// UUID or B58UUID (or read from stdin)
+        value: Option<String>,
+
+        /// Read values from file (one per line)
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<String>,
+    },
+
+    /// Print a hash of the set of IDs in a file, the same whatever their
+    /// order, form (UUID or B58UUID) or repetition
+    #[command(after_help = "EXAMPLES:\n  \
+        b58uuid fingerprint --file ids.txt\n  \
+        b58uuid encode --file ids.txt | sort -r | b58uuid fingerprint\n\n\
+The fingerprint is the BLAKE3 hash of the distinct IDs' 16-byte values,
+sorted and concatenated. Exits 1, without a fingerprint, if any line is not
+an ID.")]
+    Fingerprint {
+        /// Read IDs from file (one per line) instead of stdin
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<String>,
+    },
+
+    /// Count how often each distinct ID occurs, most frequent first; UUID
+    /// and B58UUID forms of one ID are counted together
+    #[command(after_help = "EXAMPLES:\n  \
+        b58uuid count --file access.log | head\n  \
+        b58uuid count --file ids.txt --format csv > counts.csv\n\n\
+IDs are found anywhere in a line: hyphenated or bare-hex UUIDs and any
+22-character Base58 word. Each output line is `<count> <b58uuid>`; ties are
+ordered by B58UUID.")]
+    Count {
+        /// Read lines from file instead of stdin; repeat to read several
+        /// files in turn
+        #[arg(short, long, value_name = "FILE")]
+        file: Vec<String>,
+    },
+
+    /// Measure representations of a dataset (see the subcommands)
+    Bench {
+        #[command(subcommand)]
+        command: BenchCommand,
+    },
+
+    /// Report on a dataset (see the subcommands)
+    Stats {
+        #[command(subcommand)]
+        command: StatsCommand,
     },
 
     /// Validate UUID or B58UUID format [alias: val]
     #[command(alias = "val")]
     #[command(after_help = "EXAMPLES:\n  \
         b58uuid validate 550e8400-e29b-41d4-a716-446655440000\n  \
-        b58uuid val BWBeN28Vb7cMEx7Ym8AUzs")]
+        b58uuid val BWBeN28Vb7cMEx7Ym8AUzs\n  \
+        b58uuid validate --file ids.txt\n  \
+        b58uuid validate --file ids.txt --quiet-success --stats\n  \
+        b58uuid validate --file ids.txt --stream > results.ndjson\n  \
+        b58uuid validate --check 2BjHNJQh2prqecCoP3d2NdgawRSP")]
     Validate {
-        /// UUID or B58UUID to validate
-        value: String,
+        /// UUID or B58UUID to validate (or read from stdin)
+        value: Option<String>,
+
+        /// Read values to validate from file (one per line); repeat to read
+        /// several files in turn
+        #[arg(short, long, value_name = "FILE")]
+        file: Vec<String>,
+        /// Print only invalid values; the exit code still reports failures
+        #[arg(long)]
+        quiet_success: bool,
+
+        /// Emit one JSON object per input line, blank, comment and invalid
+        /// lines included: {"line","input","valid","type","reason"}
+        #[arg(long, conflicts_with_all = ["value", "quiet_success"])]
+        stream: bool,
+
+        /// Require a Base58Check checksum (see `encode --check`): plain
+        /// UUIDs and B58UUIDs fail as "missing checksum"
+        #[arg(long, conflicts_with = "stream")]
+        check: bool,
+
+        /// Print the UUID of each valid value in uppercase hex
+        #[arg(long, conflicts_with_all = ["lowercase", "stream"])]
+        uppercase: bool,
+
+        /// Print the UUID of each valid value in lowercase hex (by default a
+        /// UUID input keeps its own case)
+        #[arg(long, conflicts_with = "stream")]
+        lowercase: bool,
     },
-}
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+    /// Check that values round-trip through encode and decode unchanged
+    /// [alias: vfy]
+    #[command(alias = "vfy")]
+    #[command(after_help = "EXAMPLES:\n  \
+        b58uuid verify BWBeN28Vb7cMEx7Ym8AUzs\n  \
+        b58uuid vfy --file ids.txt\n  \
+        b58uuid vfy --file ids.txt --quiet-success --stats\n  \
+        b58uuid vfy --file ids.txt --format ndjson | jq 'select(.ok | not)'")]
+    Verify {
+        /// UUID or B58UUID to verify (or read from stdin)
+        value: Option<String>,
 
-    // Disable colors if requested
-    if cli.no_color {
-        colored::control::set_override(false);
-    }
+        /// Read values to verify from file (one per line)
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<String>,
 
-    match cli.command {
-        Commands::Encode { uuid, file } => {
-            if let Some(file_path) = file {
-                encode_from_file(&file_path)?;
-            } else if let Some(uuid_str) = uuid {
-                encode_single(&uuid_str)?;
-            } else {
-                encode_from_stdin()?;
-            }
-        }
-        Commands::Decode { b58uuid, file } => {
-            if let Some(file_path) = file {
-                decode_from_file(&file_path)?;
-            } else if let Some(b58_str) = b58uuid {
-                decode_single(&b58_str)?;
-            } else {
-                decode_from_stdin()?;
-            }
-        }
-        Commands::Generate { count, uuid } => {
-            generate_uuids(count, uuid)?;
-        }
-        Commands::Validate { value } => {
-            validate_value(&value)?;
-        }
-    }
+        /// Print only values that fail; the exit code and --stats still
+        /// count every value
+        #[arg(long)]
+        quiet_success: bool,
+    },
 
-    Ok(())
-}
+    /// Check whether two values, each a UUID or a B58UUID, are the same UUID
+    #[command(after_help = "EXAMPLES:\n  \
+        b58uuid diff BWBeN28Vb7cMEx7Ym8AUzs 550e8400-e29b-41d4-a716-446655440000\n  \
+        b58uuid diff \"$ID_FROM_API\" \"$ID_FROM_DB\" && echo same\n\n\
+Exit codes: 0 if both are the same UUID, 1 if they differ, 2 if either is not
+a UUID or B58UUID.")]
+    Diff {
+        /// First UUID or B58UUID
+        a: String,
 
-fn encode_single(uuid_str: &str) -> Result<()> {
-    let uuid_str = uuid_str.trim();
-    match b58uuid::encode_uuid(uuid_str) {
-        Ok(b58) => {
-            println!("{}", b58.green());
-        }
-        Err(e) => {
-            eprintln!("{} {}", "Error:".red().bold(), e);
-            std::process::exit(1);
-        }
-    }
-    Ok(())
-}
+        /// Second UUID or B58UUID
+        b: String,
+    },
 
-fn encode_from_stdin() -> Result<()> {
-    let stdin = io::stdin();
-    for line in stdin.lock().lines() {
-        let line = line.context("Failed to read from stdin")?;
-        let uuid_str = line.trim();
-        if !uuid_str.is_empty() {
-            match b58uuid::encode_uuid(uuid_str) {
-                Ok(b58) => println!("{}", b58.green()),
-                Err(e) => eprintln!("{} {} - {}", "Error:".red().bold(), uuid_str, e),
-            }
-        }
-    }
-    Ok(())
-}
+    /// Print everything about a UUID or B58UUID: both forms, version,
+    /// variant, bytes and timestamp [alias: info]
+    #[command(alias = "info")]
+    #[command(after_help = "EXAMPLES:\n  \
+        b58uuid inspect BWBeN28Vb7cMEx7Ym8AUzs\n  \
+        b58uuid info 0190b2c4-5b7e-7d2a-9c1f-3e5a8b7c6d4f --format json")]
+    Inspect {
+        /// UUID or B58UUID to inspect
+        value: String,
+    },
 
-fn encode_from_file(file_path: &str) -> Result<()> {
-    let content = std::fs::read_to_string(file_path)
-        .context(format!("Failed to read file: {}", file_path))?;
+    /// Show the 128-bit integer value of a UUID or B58UUID
+    #[command(after_help = "EXAMPLES:\n  \
+        b58uuid number BWBeN28Vb7cMEx7Ym8AUzs\n  \
+        b58uuid number --parse 0x550e8400e29b41d4a716446655440000\n  \
+        b58uuid number --parse '0x550e8400 e29b41d4 a7164466 55440000'\n  \
+        b58uuid number BWBeN28Vb7cMEx7Ym8AUzs --base 16\n  \
+        b58uuid number --file ids.txt --base 2")]
+    Number {
+        /// UUID or B58UUID (or, with --parse, an integer; or read from stdin)
+        value: Option<String>,
 
-    for line in content.lines() {
-        let uuid_str = line.trim();
-        if !uuid_str.is_empty() {
-            match b58uuid::encode_uuid(uuid_str) {
-                Ok(b58) => println!("{}", b58.green()),
-                Err(e) => eprintln!("{} {} - {}", "Error:".red().bold(), uuid_str, e),
-            }
-        }
-    }
-    Ok(())
+        /// Read values from file (one per line)
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<String>,
+
+        /// Read VALUE as an integer (decimal, or hex with a 0x prefix;
+        /// whitespace is ignored) and print its B58UUID
+        #[arg(long)]
+        parse: bool,
+
+        /// Print the integer in this base, zero-padded to the full 128-bit
+        /// width (base 58 is the B58UUID itself)
+        #[arg(
+            long,
+            conflicts_with = "parse",
+            value_parser = clap::builder::PossibleValuesParser::new(number::BASES)
+                .map(|base| base.parse::<u32>().unwrap())
+        )]
+        base: Option<u32>,
+    },
+
+    /// Show each step of decoding a B58UUID (digit values and running total)
+    #[command(after_help = "EXAMPLES:\n  \
+        b58uuid trace BWBeN28Vb7cMEx7Ym8AUzs\n  \
+        b58uuid trace BWBeN28Vb7cMEx7Ym8AUzs --json")]
+    Trace {
+        /// B58UUID to trace
+        b58uuid: String,
+
+        /// Emit the trace as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print a shell completion script to stdout
+    #[command(after_help = "EXAMPLES:\n  \
+        b58uuid completions bash > /etc/bash_completion.d/b58uuid\n  \
+        b58uuid completions zsh > \"${fpath[1]}/_b58uuid\"\n  \
+        b58uuid completions fish > ~/.config/fish/completions/b58uuid.fish")]
+    Completions {
+        /// Shell to generate the script for
+        shell: clap_complete::Shell,
+    },
 }
 
-fn decode_single(b58_str: &str) -> Result<()> {
-    let b58_str = b58_str.trim();
-    match b58uuid::decode_to_uuid(b58_str) {
-        Ok(uuid) => {
-            println!("{}", uuid.green());
-        }
-        Err(e) => {
-            eprintln!("{} {}", "Error:".red().bold(), e);
-            std::process::exit(1);
-        }
-    }
-    Ok(())
+#[derive(Subcommand)]
+enum StatsCommand {
+    /// Histogram of B58UUID lengths when encoding a file of UUIDs; every
+    /// length other than 22 is flagged, and the exit code is 1 if any occur
+    #[command(after_help = "EXAMPLES:\n  \
+        b58uuid stats length --file ids.txt\n  \
+        b58uuid generate -n 100000 --uuid | b58uuid stats length")]
+    Length {
+        /// Read UUIDs from file (one per line) instead of stdin
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<String>,
+    },
 }
 
-fn decode_from_stdin() -> Result<()> {
-    let stdin = io::stdin();
-    for line in stdin.lock().lines() {
-        let line = line.context("Failed to read from stdin")?;
-        let b58_str = line.trim();
-        if !b58_str.is_empty() {
-            match b58uuid::decode_to_uuid(b58_str) {
-                Ok(uuid) => println!("{}", uuid.green()),
-                Err(e) => eprintln!("{} {} - {}", "Error:".red().bold(), b58_str, e),
-            }
-        }
-    }
-    Ok(())
+#[derive(Subcommand)]
+enum BenchCommand {
+    /// Size of the IDs in a file as UUID text, 16-byte binary and B58UUID,
+    /// with parse and sort timings for each
+    #[command(after_help = "EXAMPLES:\n  \
+        b58uuid bench storage --file ids.txt\n  \
+        b58uuid bench storage --file ids.txt --format json > storage.json")]
+    Storage {
+        /// Read IDs from file (one per line) instead of stdin
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<String>,
+    },
 }
 
-fn decode_from_file(file_path: &str) -> Result<()> {
-    let content = std::fs::read_to_string(file_path)
-        .context(format!("Failed to read file: {}", file_path))?;
+/// Flags shared by the batch forms of `encode` and `decode`.
+#[derive(Args)]
+struct BatchArgs {
+    /// Drop batch lines whose UUID version is in this list (e.g. 1 or 1,6)
+    #[arg(
+        long,
+        value_name = "N",
+        value_delimiter = ',',
+        conflicts_with = "value",
+        value_parser = clap::value_parser!(u8).range(0..16)
+    )]
+    exclude_version: Vec<u8>,
 
-    for line in content.lines() {
-        let b58_str = line.trim();
-        if !b58_str.is_empty() {
-            match b58uuid::decode_to_uuid(b58_str) {
-                Ok(uuid) => println!("{}", uuid.green()),
-                Err(e) => eprintln!("{} {} - {}", "Error:".red().bold(), b58_str, e),
-            }
-        }
-    }
-    Ok(())
-}
+    /// Prior output to compare against, line by line; --stats then counts
+    /// added, changed and unchanged outputs
+    #[arg(long, value_name = "FILE", conflicts_with = "value")]
+    baseline: Option<String>,
 
-fn generate_uuids(count: usize, as_uuid: bool) -> Result<()> {
-    for _ in 0..count {
-        if as_uuid {
-            let uuid = uuid::Uuid::new_v4();
-            println!("{}", uuid.to_string().green());
-        } else {
-            let b58 = b58uuid::generate();
-            println!("{}", b58.green());
-        }
-    }
-    Ok(())
-}
+    /// Print only outputs that differ from the --baseline line at the same index
+    #[arg(long, requires = "baseline", conflicts_with = "value")]
+    changed_only: bool,
 
-fn validate_value(value: &str) -> Result<()> {
-    let value = value.trim();
+    /// Write results to a file (truncating it) instead of stdout, without
+    /// color; errors stay on stderr
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<String>,
 
-    // Try to decode as B58UUID
-    if let Ok(uuid) = b58uuid::decode_to_uuid(value) {
-        println!("{} Valid B58UUID", "✓".green().bold());
-        println!("  B58UUID: {}", value.cyan());
-        println!("  UUID:    {}", uuid.cyan());
-        return Ok(());
-    }
+    /// Checkpoint progress in <FILE>.progress and, when it exists, continue
+    /// from there instead of starting over (the input must be unchanged)
+    #[arg(long, requires = "output", conflicts_with_all = ["value", "baseline"])]
+    resume: bool,
 
-    // Try to encode as UUID
-    if let Ok(b58) = b58uuid::encode_uuid(value) {
-        println!("{} Valid UUID", "✓".green().bold());
-        println!("  UUID:    {}", value.cyan());
-        println!("  B58UUID: {}", b58.cyan());
-        return Ok(());
-    }
+    /// Show a progress bar on stderr while a batch runs; only drawn when
+    /// stderr is a terminal
+    #[arg(long, conflicts_with = "value")]
+    progress: bool,
+
+    /// After the run, print error counts grouped by reason to stderr
+    #[arg(long, conflicts_with = "value")]
+    error_summary: bool,
 
-    eprintln!("{} Invalid format", "✗".red().bold());
-    eprintln!("  Value: {}", value);
-    eprintln!("  Expected: UUID (36 chars) or B58UUID (22 chars)");
-    std::process::exit(1);
+    /// Exit with code 1 after the run if any line failed to convert (by
+    /// default only a single value on the command line does); --csv and
+    /// --where also drop the rows that failed
+    #[arg(long)]
+    strict: bool,
+
+    /// Convert lines on N threads; output order matches the input whatever N
+    #[arg(
+        long,
+        value_name = "N",
+        default_value = "1",
+        conflicts_with = "value",
+        value_parser = clap::value_parser!(u16).range(1..)
+    )]
+    jobs: u16,
+
+    /// Read every text file in a tar archive (.tar or .tar.gz) instead
+    #[arg(long, value_name = "ARCHIVE", conflicts_with_all = ["value", "file"])]
+    tar: Option<String>,
+
+    /// Prefix each result with the file it came from (the archive member
+    /// with --tar)
+    #[arg(long, conflicts_with = "value")]
+    with_source: bool,
+
+    /// Write a versioned mapping document (tool version, encoding, alphabet,
+    /// timestamp and every conversion) instead of plain results
+    #[arg(long, value_enum, value_name = "FORMAT", conflicts_with_all = ["resume", "with_source"])]
+    map_format: Option<mapping::MapFormat>,
+
+    /// Take the value from byte offsets START:END of each line (end
+    /// exclusive), for fixed-width files
+    #[arg(long, value_name = "START:END", conflicts_with = "value")]
+    columns: Option<ColumnRange>,
+
+    /// With --columns, print each whole line with the value replaced in
+    /// place (padded to the column width)
+    #[arg(long, requires = "columns")]
+    keep_layout: bool,
+
+    /// Sort the results by their converted value
+    #[arg(long, conflicts_with_all = ["value", "resume"])]
+    sort: bool,
+
+    /// Drop results whose converted value was already written, keeping the
+    /// first in input order (memory grows with the distinct values; with
+    /// --sort, sorted and spilled like --sort)
+    #[arg(short, long, conflicts_with_all = ["value", "resume"])]
+    unique: bool,
+
+    /// Memory for --sort before sorted runs are spilled to temporary files
+    /// (default: no limit)
+    #[arg(long, value_name = "MB", value_parser = clap::value_parser!(u64).range(1..))]
+    max_memory: Option<u64>,
+
+    /// Drop results repeating one of the last N written, keeping input
+    /// order in fixed memory (repeats further apart are kept)
+    #[arg(
+        long,
+        value_name = "N",
+        conflicts_with_all = ["value", "unique", "resume"],
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    dedupe_window: Option<u64>,
+
+    /// With CSV input, convert the --input-field column only in rows where
+    /// COL equals VALUE; the whole CSV is written, other rows unchanged
+    #[arg(
+        long = "where",
+        value_name = "COL=VALUE",
+        conflicts_with_all = [
+            "value", "tar", "columns", "resume", "baseline", "with_source",
+            "map_format", "sort", "unique", "dedupe_window"
+        ]
+    )]
+    where_filter: Option<WhereFilter>,
+
+    /// Read the input as CSV and write it back out with the --column column
+    /// converted in every row, the header and other columns untouched
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "value", "tar", "columns", "resume", "baseline", "with_source",
+            "map_format", "sort", "unique", "dedupe_window"
+        ]
+    )]
+    csv: bool,
+
+    /// Column of --csv or --where input to convert: a header name or a
+    /// 1-based index (default: --input-field, else the first column)
+    #[arg(long, value_name = "NAME|INDEX")]
+    column: Option<String>,
+
+    /// Keep converting stdin as a co-process: each result is flushed as it
+    /// is written, and a batch ends at a --sentinel line (default: a blank
+    /// line), which is echoed once the batch's results are out
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "value", "file", "tar", "output", "baseline", "map_format", "sort",
+            "unique", "dedupe_window", "where_filter", "csv"
+        ]
+    )]
+    serve: bool,
+
+    /// Line that ends a batch in --serve mode
+    #[arg(long, value_name = "STR", requires = "serve")]
+    sentinel: Option<String>,
+}
+
+impl BatchArgs {
+    /// Input for the command: `--tar` if given, otherwise the usual
+    /// positional value, `--file` (repeated for several files) or stdin.
+    fn source(&self, value: Option<String>, files: Vec<String>) -> InputSource {
+        match &self.tar {
+            Some(archive) => InputSource::Tar(archive.clone()),
+            None => InputSource::resolve_files(value, files),
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    match run() {
+        // The reader went away (e.g. `| head`): stop quietly, as a filter
+        // killed by SIGPIPE would
+        Err(e) if is_broken_pipe(&e) => Ok(()),
+        result => result,
+    }
+}
+
+fn is_broken_pipe(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause
+            .downcast_ref::<io::Error>()
+            .is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe)
+    })
+}
+
+fn run() -> Result<()> {
+    let cli = Cli::parse();
+
+    let color = if cli.no_color {
+        ColorChoice::Never
+    } else {
+        cli.color
+    };
+    let no_color = std::env::var_os("NO_COLOR").is_some();
+    if let Some(enabled) = color_override(color, no_color, io::stdout().is_terminal(), enable_ansi)
+    {
+        colored::control::set_override(enabled);
+    }
+
+    errors::set_format(cli.error_format);
+    errors::set_quiet(cli.quiet);
+    alphabet::set(cli.alphabet);
+    output::set_buffer_size(cli.buffer_size.map(|bytes| bytes as usize));
+    if let Some(chars) = &cli.trim_chars {
+        input::set_trim_chars(chars);
+    }
+    if cli.alphabet != b58uuid_cli::Alphabet::Bitcoin
+        && matches!(
+            cli.command,
+            Commands::Trace { .. } | Commands::Sortkey { .. }
+        )
+    {
+        bail!("trace and sortkey follow the Bitcoin alphabet; --alphabet does not apply");
+    }
+    if cli.verbose {
+        log::init(cli.log_target)?;
+    }
+    let input_opts = InputOptions {
+        format: cli.input_format,
+        field: cli.input_field,
+        whitespace: cli.whitespace,
+        comments: match (cli.comments, cli.no_comments) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        },
+        null: cli.null,
+    };
+    let output_opts = OutputOptions {
+        format: cli.format,
+        line_ending: if cli.null {
+            LineEnding::Nul
+        } else {
+            cli.line_ending
+        },
+        flush: cli.flush,
+    };
+    if cli.null && matches!(cli.format, Format::Json | Format::Table) {
+        bail!("--null ends each record with a NUL byte; use --format text, ndjson or csv with it");
+    }
+    if cli.format == Format::BinaryPairs {
+        match &cli.command {
+            Commands::Encode {
+                check: false,
+                zero_handling: ZeroHandling::Pad,
+                batch,
+                ..
+            } if batch.map_format.is_none() => {}
+            _ => bail!(
+                "--format binary-pairs is only supported by encode (without --check, \
+                 --map-format or --zero-handling trim)"
+            ),
+        }
+    }
+    match cli.command {
+        Commands::Encode {
+            uuid,
+            file,
+            batch,
+            check,
+            annotate_savings,
+            tolerant_hyphens,
+            unicode_normalize,
+            byte_order,
+            prefix_map,
+            zero_handling,
+            inline,
+            from_bytes,
+        } => {
+            if prefix_map.is_some() && cli.format != Format::Text {
+                bail!("--prefix-map writes plain B58UUIDs and takes no --format");
+            }
+            let label_errors = file.len() > 1;
+            let source = batch.source(uuid, file);
+            let mut opts = BatchOptions::new(batch, cli.stats, cli.profile, cli.yes, input_opts)?;
+            if inline {
+                return convert_inline(source, false, output_opts, &opts);
+            }
+            if from_bytes {
+                return encode_from_bytes(source, output_opts, &opts);
+            }
+            opts.label_errors = label_errors;
+            opts.annotate_savings = annotate_savings;
+            opts.tolerant_hyphens = tolerant_hyphens;
+            opts.unicode_normalize = unicode_normalize;
+            opts.byte_order = byte_order;
+            opts.prefix_map = prefix_map;
+            let direction = match (check, zero_handling) {
+                (true, _) => Direction::EncodeCheck,
+                (false, ZeroHandling::Pad) => Direction::Encode,
+                (false, ZeroHandling::Trim) => Direction::EncodeTrimmed,
+            };
+            convert(source, direction, output_opts, &opts)?;
+            opts.report_profile(output_opts.format);
+            opts.exit_on_failure();
+        }
+        Commands::Decode {
+            b58uuid,
+            file,
+            batch,
+            all_forms,
+            uppercase,
+            lowercase,
+            to_bytes,
+            urn,
+            strict_b58,
+            strict_length,
+            byte_order,
+            inline,
+        } => {
+            output::set_uuid_case(uuid_case(uppercase, lowercase));
+            let label_errors = file.len() > 1;
+            let source = batch.source(b58uuid, file);
+            let mut opts = BatchOptions::new(batch, cli.stats, cli.profile, cli.yes, input_opts)?;
+            if inline {
+                return convert_inline(source, true, output_opts, &opts);
+            }
+            opts.label_errors = label_errors;
+            opts.all_forms = all_forms;
+            opts.byte_order = byte_order;
+            opts.urn = urn;
+            opts.strict_b58 = strict_b58;
+            opts.strict_length = strict_length || strict_b58;
+            let direction = if to_bytes {
+                Direction::DecodeBytes
+            } else {
+                Direction::Decode
+            };
+            convert(source, direction, output_opts, &opts)?;
+            opts.report_profile(output_opts.format);
+            opts.exit_on_failure();
+        }
+        Commands::Transcode {
+            value,
+            file,
+            batch,
+            to,
+            input_encoding,
+            output_encoding,
+            urn,
+        } => {
+            if batch.map_format.is_some() {
+                bail!("--map-format needs one direction; use encode or decode");
+            }
+            let direction = match output_encoding {
+                Some(to) => Direction::Recode(input_encoding, to),
+                None => Direction::Transcode(to),
+            };
+            let label_errors = file.len() > 1;
+            let source = batch.source(value, file);
+            let mut opts = BatchOptions::new(batch, cli.stats, cli.profile, cli.yes, input_opts)?;
+            opts.label_errors = label_errors;
+            opts.urn = urn;
+            convert(source, direction, output_opts, &opts)?;
+            opts.report_profile(output_opts.format);
+            opts.exit_on_failure();
+        }
+        Commands::Generate {
+            spec,
+            count,
+            uuid,
+            both,
+            delimiter,
+            output,
+            atomic,
+            shards,
+            shard_prefix,
+            rng,
+            version,
+            namespace,
+            name,
+            file,
+            seed,
+            seed_file,
+            interval,
+            infinite,
+            duration,
+            rate,
+            warn_duplicates,
+            pattern,
+            max_attempts,
+        } => {
+            let spec = match &spec {
+                Some(path) => spec::read(path)?,
+                None => spec::GenerateSpec::default(),
+            };
+            let count = count.or(spec.count);
+            let names = if name.is_empty() {
+                spec.name.into_iter().collect()
+            } else {
+                name
+            };
+            // Resolved here rather than by clap: a B58UUID namespace is read
+            // in the --alphabet, which is only set once parsing is done
+            let namespace = match namespace {
+                Some(namespace) => {
+                    Some(generate::parse_namespace(&namespace).map_err(|reason| {
+                        anyhow::anyhow!(
+                            "invalid value '{}' for '--namespace': {}",
+                            namespace,
+                            reason
+                        )
+                    })?)
+                }
+                None => spec.namespace,
+            };
+            let (uuid, both, delimiter) = if uuid || both || delimiter.is_some() {
+                (uuid, both, delimiter)
+            } else {
+                (
+                    spec.uuid.unwrap_or(false),
+                    spec.both.unwrap_or(false),
+                    spec.delimiter,
+                )
+            };
+            let atomic = atomic || spec.atomic.unwrap_or(false);
+            let version = version
+                .or(spec.version)
+                .unwrap_or(generate::UuidVersion::V4);
+            // Names resolve like the input of every other command: --name,
+            // then --file, then stdin (unless it is a terminal)
+            let names = match InputSource::resolve_files(None, file.clone()) {
+                _ if !names.is_empty() || version != generate::UuidVersion::V5 => names,
+                InputSource::Stdin if io::stdin().is_terminal() => names,
+                source => source
+                    .lines(&input_opts)?
+                    .filter(|line| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+                    .map(|line| line.map(|line| line.trim().to_string()))
+                    .collect::<Result<_>>()?,
+            };
+            let interval = interval.or(spec.interval);
+            let infinite = infinite || spec.infinite.unwrap_or(false);
+            let duration = duration.or(spec.duration);
+            let rate = rate.or(spec.rate);
+            let warn_duplicates = warn_duplicates || spec.warn_duplicates.unwrap_or(false);
+            let pattern = pattern.or(spec.pattern);
+            // Each pair is one choice: a flag for either side replaces the
+            // spec's choice entirely
+            let (output, shards) = if output.is_some() || shards.is_some() {
+                (output, shards)
+            } else {
+                (spec.output, spec.shards)
+            };
+            let (seed, seed_file) = if seed.is_some() || seed_file.is_some() {
+                (seed, seed_file)
+            } else {
+                (spec.seed, spec.seed_file)
+            };
+            // A seed asks for the seeded generator unless --rng says otherwise
+            let rng = rng
+                .or(spec.rng)
+                .unwrap_or(if seed.is_some() || seed_file.is_some() {
+                    generate::RngKind::Seeded
+                } else {
+                    generate::RngKind::Os
+                });
+            let shard_prefix = shard_prefix.or(spec.shard_prefix);
+            let max_attempts = max_attempts.or(spec.max_attempts);
+            if output.is_some() && shards.is_some() {
+                bail!("output and shards cannot be used together");
+            }
+            if shards.is_some() && (interval.is_some() || pattern.is_some()) {
+                bail!("shards cannot be combined with interval or match");
+            }
+            if duration.is_some() && (shards.is_some() || interval.is_some()) {
+                bail!("duration cannot be combined with shards or interval");
+            }
+            if infinite
+                && (count.is_some()
+                    || atomic
+                    || shards.is_some()
+                    || interval.is_some()
+                    || duration.is_some())
+            {
+                bail!(
+                    "infinite cannot be combined with count, atomic, shards, interval or duration"
+                );
+            }
+            if rate.is_some() && duration.is_none() {
+                bail!("--rate requires --duration");
+            }
+            if seed.is_some() && seed_file.is_some() {
+                bail!("seed and seed-file cannot be used together");
+            }
+            if atomic && output.is_none() {
+                bail!("--atomic requires --output");
+            }
+            if shard_prefix.is_some() && shards.is_none() {
+                bail!("--shard-prefix requires --shards");
+            }
+            if max_attempts.is_some() && pattern.is_none() {
+                bail!("--max-attempts requires --match");
+            }
+            if uuid && both {
+                bail!("--both prints the UUID and the B58UUID and cannot be combined with --uuid");
+            }
+            if delimiter.is_some() && !both {
+                bail!("--delimiter requires --both");
+            }
+            if version == generate::UuidVersion::V5 {
+                if names.is_empty() {
+                    bail!(
+                        "--version 5 needs --name, --file or names on stdin: a v5 UUID is the hash of a namespace and a name"
+                    );
+                }
+                if namespace.is_none() {
+                    bail!("--version 5 needs --namespace (dns, url, oid, x500 or a UUID)");
+                }
+                if count.is_some()
+                    || shards.is_some()
+                    || interval.is_some()
+                    || duration.is_some()
+                    || infinite
+                    || pattern.is_some()
+                    || rng == generate::RngKind::Seeded
+                {
+                    bail!("--version 5 makes one UUID per --name; --count, --shards, --interval, --duration, --infinite, --match and --rng seeded do not apply");
+                }
+            } else if namespace.is_some() || !names.is_empty() || !file.is_empty() {
+                bail!("--namespace, --name and --file apply only to --version 5");
+            }
+            let count = count.unwrap_or(1);
+            let shown = match (uuid, both) {
+                (_, true) => generate::Shown::Both(delimiter.unwrap_or_else(|| "\t".to_string())),
+                (true, false) => generate::Shown::Uuid,
+                (false, false) => generate::Shown::B58uuid,
+            };
+            let shard_prefix = shard_prefix.unwrap_or_else(|| "shard_".to_string());
+            let max_attempts = max_attempts.unwrap_or(10_000_000);
+            let seed = match (seed, seed_file) {
+                (Some(seed), _) => Some(generate::Seed::Number(seed)),
+                (None, Some(path)) => Some(generate::Seed::from_file(&path)?),
+                (None, None) => None,
+            };
+            match (rng, seed.is_some()) {
+                (generate::RngKind::Os, true) => {
+                    bail!("--seed and --seed-file use the seeded generator and cannot be combined with --rng os")
+                }
+                (generate::RngKind::Seeded, false) => {
+                    bail!("--rng seeded requires --seed or --seed-file")
+                }
+                _ => {}
+            }
+            if version == generate::UuidVersion::V7 && rng == generate::RngKind::Seeded {
+                bail!("--version 7 embeds the current time and cannot be reproduced with --rng seeded");
+            }
+            log::verbose(&generate::describe_rng(rng, seed));
+            if output.is_some() || shards.is_some() {
+                // Files never get color
+                colored::control::set_override(false);
+            }
+            let mut source = generate::UuidSource::new(rng, seed).version(version);
+            if warn_duplicates {
+                source = source.warn_duplicates();
+            }
+            if let Some(pattern) = &pattern {
+                let regex = regex::Regex::new(pattern)
+                    .context(format!("Invalid --match pattern: {}", pattern))?;
+                for warning in generate::match_warnings(pattern, alphabet::get().digits()) {
+                    eprintln!(
+                        "{} --match may be infeasible: {}",
+                        "Warning:".yellow().bold(),
+                        warning
+                    );
+                }
+                source = source.matching(regex, max_attempts);
+            }
+            let emit = |out: &mut dyn OutputWriter| match (namespace, duration, interval) {
+                (Some(namespace), _, _) => generate::generate_named(out, namespace, &names, &shown),
+                (None, None, None) if infinite => {
+                    generate::generate_infinite(out, &shown, &mut source)
+                }
+                (None, Some(duration), _) => {
+                    generate::generate_for(out, &shown, &mut source, duration, rate)
+                }
+                (None, None, Some(ms)) => generate::generate_interval(
+                    out,
+                    count,
+                    &shown,
+                    &mut source,
+                    Duration::from_millis(ms),
+                ),
+                (None, None, None) => generate::generate(out, count, &shown, &mut source),
+            };
+            match (&output, shards) {
+                (Some(path), _) => confirm_overwrite(&[Path::new(path)], cli.yes)?,
+                (None, Some(shards)) => {
+                    let paths = generate::shard_paths(&shard_prefix, shards as usize);
+                    let paths: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+                    confirm_overwrite(&paths, cli.yes)?
+                }
+                (None, None) => {}
+            }
+            match (output, shards) {
+                (Some(path), _) if atomic => {
+                    // Ctrl-C would otherwise leave the temporary file behind;
+                    // --interval and --duration handle it themselves, ending
+                    // the run early but complete
+                    if interval.is_none() && duration.is_none() {
+                        let tmp_path = temp_path_for(Path::new(&path));
+                        ctrlc::set_handler(move || {
+                            let _ = fs::remove_file(&tmp_path);
+                            std::process::exit(130);
+                        })?;
+                    }
+                    write_atomically(Path::new(&path), |out| {
+                        write_records(output_opts, Box::new(out), false, emit)
+                    })?
+                }
+                (Some(path), _) => write_to_file(Path::new(&path), |out| {
+                    write_records(output_opts, Box::new(out), false, emit)
+                })?,
+                (None, Some(shards)) => generate::write_shards(
+                    output_opts,
+                    count,
+                    &shown,
+                    (rng, seed, version),
+                    warn_duplicates,
+                    shards as usize,
+                    &shard_prefix,
+                )?,
+                // Buffered, so --infinite writes in batches between its
+                // flushes rather than a line at a time
+                (None, None) if infinite => write_records(
+                    output_opts,
+                    Box::new(BufWriter::with_capacity(
+                        output::buffer_size(),
+                        io::stdout().lock(),
+                    )),
+                    false,
+                    emit,
+                )?,
+                (None, None) => write_records(output_opts, output::stdout(), false, emit)?,
+            }
+            if let (true, Some(attempts)) = (cli.stats, source.attempts()) {
+                let produced = source.produced();
+                eprintln!(
+                    "Matched {} values in {} attempts ({:.1} per value)",
+                    produced,
+                    attempts,
+                    attempts as f64 / produced.max(1) as f64
+                );
+            }
+        }
+        Commands::GenTable {
+            count,
+            columns,
+            seed,
+        } => {
+            // A table is CSV unless a structured format was asked for
+            let mut table_opts = output_opts;
+            if table_opts.format == Format::Text {
+                table_opts.format = Format::Csv;
+            }
+            write_records(table_opts, Box::new(io::stdout().lock()), false, |out| {
+                generate::generate_table(out, count, &columns, seed)
+            })?;
+        }
+        Commands::Derive { input, hash } => {
+            write_records(output_opts, Box::new(io::stdout().lock()), true, |out| {
+                derive::derive(&input, hash, out)
+            })?;
+        }
+        Commands::V8 { hex, file } => {
+            write_records(output_opts, Box::new(io::stdout().lock()), true, |out| {
+                derive::v8(hex.as_deref(), file.as_deref(), out)
+            })?;
+        }
+        Commands::UrlRewrite { line, file, decode } => {
+            let source = InputSource::resolve(line, file);
+            let single = matches!(source, InputSource::Value(_));
+            // Lines are rewritten as text whatever their contents
+            let text = InputOptions {
+                format: InputFormat::Text,
+                field: None,
+                whitespace: false,
+                comments: Some(false),
+                null: false,
+            };
+            let lines = source.lines(&text)?;
+            write_records(output_opts, Box::new(io::stdout().lock()), single, |out| {
+                rewrite::url_rewrite(lines, decode, out)
+            })?;
+        }
+        Commands::SqlRewrite {
+            file,
+            output,
+            decode,
+        } => {
+            let mut dump = Vec::new();
+            InputSource::resolve(None, file)
+                .reader()?
+                .read_to_end(&mut dump)
+                .context("Failed to read SQL dump")?;
+            let (rewritten, replaced) = rewrite::sql_rewrite(&dump, decode)?;
+            match output {
+                Some(path) => {
+                    let path = Path::new(&path);
+                    confirm_overwrite(&[path], cli.yes)?;
+                    write_to_file(path, |out| Ok(out.write_all(&rewritten)?))?;
+                }
+                None => {
+                    let mut out = io::stdout().lock();
+                    out.write_all(&rewritten)?;
+                    out.flush()?;
+                }
+            }
+            eprintln!(
+                "Rewrote {} {} literals",
+                replaced,
+                if decode { "B58UUID" } else { "UUID" }
+            );
+        }
+        Commands::Map {
+            file,
+            output,
+            direction,
+            unique,
+        } => {
+            let lines = InputSource::resolve(None, file).lines(&input_opts)?;
+            let emit = |out: &mut dyn OutputWriter| lookup::map(lines, direction, unique, out);
+            match output {
+                Some(path) => {
+                    colored::control::set_override(false);
+                    let path = Path::new(&path);
+                    confirm_overwrite(&[path], cli.yes)?;
+                    write_to_file(path, |out| {
+                        write_records(output_opts, Box::new(out), false, emit)
+                    })?;
+                }
+                None => write_records(output_opts, output::stdout(), false, emit)?,
+            }
+        }
+        Commands::ReverseMap { map, value, file } => {
+            let source = InputSource::resolve(value, file);
+            let single = matches!(source, InputSource::Value(_));
+            let lines = source.lines(&input_opts)?;
+            let mut all_found = true;
+            write_records(output_opts, Box::new(io::stdout().lock()), single, |out| {
+                all_found = mapping::reverse_map(&map, lines, out)?;
+                Ok(())
+            })?;
+            if !all_found {
+                std::process::exit(1);
+            }
+        }
+        Commands::Sortkey { value, file } => {
+            let source = InputSource::resolve(value, file);
+            let single = matches!(source, InputSource::Value(_));
+            write_records(
+                output_opts,
+                Box::new(io::stdout().lock()),
+                single,
+                |out| match source {
+                    InputSource::Value(value) => sortkey::sortkey_value(&value, out),
+                    source => sortkey::sortkey_lines(source.lines(&input_opts)?, out),
+                },
+            )?;
+        }
+        Commands::Fingerprint { file } => {
+            let lines = InputSource::resolve(None, file).lines(&input_opts)?;
+            let mut written = false;
+            write_records(output_opts, Box::new(io::stdout().lock()), true, |out| {
+                written = fingerprint::fingerprint(lines, out)?;
+                Ok(())
+            })?;
+            if !written {
+                std::process::exit(1);
+            }
+        }
+        Commands::Count { file } => {
+            let lines = InputSource::resolve_files(None, file).lines(&input_opts)?;
+            write_records(output_opts, Box::new(io::stdout().lock()), false, |out| {
+                count::count(lines, cli.stats, out)
+            })?;
+        }
+        Commands::Bench {
+            command: BenchCommand::Storage { file },
+        } => {
+            let lines = InputSource::resolve(None, file).lines(&input_opts)?;
+            write_records(output_opts, Box::new(io::stdout().lock()), false, |out| {
+                bench::storage(lines, out)
+            })?;
+        }
+        Commands::Stats {
+            command: StatsCommand::Length { file },
+        } => {
+            let lines = InputSource::resolve(None, file).lines(&input_opts)?;
+            let mut all_expected = true;
+            write_records(output_opts, Box::new(io::stdout().lock()), false, |out| {
+                all_expected = length::length_stats(lines, out)?;
+                Ok(())
+            })?;
+            if !all_expected {
+                std::process::exit(1);
+            }
+        }
+        Commands::Validate {
+            value,
+            file,
+            quiet_success,
+            stream,
+            check,
+            uppercase,
+            lowercase,
+        } => {
+            output::set_uuid_case(uuid_case(uppercase, lowercase));
+            let source = InputSource::resolve_files(value, file);
+            if stream {
+                // Every line is reported, so line numbers match the input
+                let every_line = InputOptions {
+                    comments: Some(false),
+                    ..input_opts.clone()
+                };
+                let lines = source.lines(&every_line)?;
+                let mut out = output::raw_writer(output_opts, Box::new(io::stdout().lock()));
+                if !validate_stream(lines, &mut out)? {
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
+            let single = matches!(source, InputSource::Value(_));
+            let mut all_valid = true;
+            write_records(output_opts, Box::new(io::stdout().lock()), single, |out| {
+                let (line_record, value_record) = if check {
+                    (
+                        check::line_record as RecordFn,
+                        check::value_record as RecordFn,
+                    )
+                } else {
+                    (line_record as RecordFn, value_record as RecordFn)
+                };
+                all_valid = match source {
+                    InputSource::Value(value) => {
+                        validate_value(&value, value_record, quiet_success, out)?
+                    }
+                    source => {
+                        let lines = source.lines(&input_opts)?;
+                        validate_lines(lines, line_record, cli.stats, quiet_success, out)?
+                    }
+                };
+                Ok(())
+            })?;
+            if !all_valid {
+                std::process::exit(1);
+            }
+        }
+        Commands::Verify {
+            value,
+            file,
+            quiet_success,
+        } => {
+            let source = InputSource::resolve(value, file);
+            let single = matches!(source, InputSource::Value(_));
+            let lines = source.lines(&input_opts)?;
+            let mut all_ok = true;
+            write_records(output_opts, Box::new(io::stdout().lock()), single, |out| {
+                all_ok = verify::verify(lines, cli.stats, quiet_success, out)?;
+                Ok(())
+            })?;
+            if !all_ok {
+                std::process::exit(1);
+            }
+        }
+        Commands::Diff { a, b } => {
+            let mut outcome = diff::Outcome::Same;
+            write_records(output_opts, Box::new(io::stdout().lock()), true, |out| {
+                outcome = diff::diff(&a, &b, out)?;
+                Ok(())
+            })?;
+            if outcome != diff::Outcome::Same {
+                std::process::exit(outcome as i32);
+            }
+        }
+        Commands::Inspect { value } => {
+            let mut valid = false;
+            write_records(output_opts, Box::new(io::stdout().lock()), true, |out| {
+                valid = inspect::inspect(&value, out)?;
+                Ok(())
+            })?;
+            if !valid {
+                std::process::exit(1);
+            }
+        }
+        Commands::Number {
+            value,
+            file,
+            parse,
+            base,
+        } => {
+            let source = InputSource::resolve(value, file);
+            let single = matches!(source, InputSource::Value(_));
+            write_records(
+                output_opts,
+                Box::new(io::stdout().lock()),
+                single,
+                |out| match source {
+                    InputSource::Value(value) => number::number(&value, parse, base, out),
+                    source => number::number_lines(source.lines(&input_opts)?, parse, base, out),
+                },
+            )?;
+        }
+        Commands::Trace { b58uuid, json } => {
+            let mut out = output::raw_writer(output_opts, Box::new(io::stdout().lock()));
+            trace::trace_value(&b58uuid, json, &mut out)?;
+        }
+        Commands::Completions { shell } => {
+            let mut command = Cli::command();
+            let name = command.get_name().to_string();
+            // clap_complete panics on a failed write, so the script is built
+            // in memory and a closed pipe surfaces as an error like elsewhere
+            let mut script = Vec::new();
+            clap_complete::generate(shell, &mut command, name, &mut script);
+            io::stdout().write_all(&script)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Turn on ANSI escape processing for the Windows console. Older consoles
+/// (cmd.exe before Windows 10) cannot, and would print raw escape codes.
+#[cfg(windows)]
+fn enable_ansi() -> bool {
+    enable_ansi_support::enable_ansi_support().is_ok()
+}
+
+#[cfg(not(windows))]
+fn enable_ansi() -> bool {
+    true
+}
+
+/// Whether to force colors on or off, or `None` to leave them on. always
+/// and never win over NO_COLOR; auto colors only a terminal stdout, and not
+/// if NO_COLOR is set or the console cannot show colors (`ansi`, only asked
+/// when it matters, since on Windows it switches the console mode).
+fn color_override(
+    choice: ColorChoice,
+    no_color: bool,
+    terminal: bool,
+    ansi: fn() -> bool,
+) -> Option<bool> {
+    match choice {
+        ColorChoice::Always => Some(ansi()),
+        ColorChoice::Never => Some(false),
+        ColorChoice::Auto if no_color || !terminal || !ansi() => Some(false),
+        ColorChoice::Auto => None,
+    }
+}
+
+/// Run `emit` against a writer built from `opts` over `out`, then finish the
+/// writer.
+fn write_records<'a, F>(
+    opts: OutputOptions,
+    out: Box<dyn Write + 'a>,
+    single: bool,
+    emit: F,
+) -> Result<()>
+where
+    F: FnOnce(&mut dyn OutputWriter) -> Result<()>,
+{
+    let mut writer = output::writer(opts, out, single);
+    emit(&mut *writer)?;
+    writer.finish()
+}
+
+/// `--inline`: rewrite the UUIDs (or, with `decode`, the B58UUIDs) found in
+/// each line in place, as `url-rewrite` does, to stdout or `--output`.
+fn convert_inline(
+    source: InputSource,
+    decode: bool,
+    output_opts: OutputOptions,
+    opts: &BatchOptions,
+) -> Result<()> {
+    let single = matches!(source, InputSource::Value(_));
+    // Lines are rewritten as text whatever their contents
+    let text = InputOptions {
+        format: InputFormat::Text,
+        field: None,
+        whitespace: false,
+        comments: Some(false),
+        null: false,
+    };
+    let lines = source.lines(&text)?;
+    write_output(output_opts, opts, single, |out| {
+        rewrite::url_rewrite(lines, decode, out)
+    })
+}
+
+/// `encode --from-bytes`: encode the one UUID given as hex in `source`'s
+/// value, or as raw bytes or hex in its file or stdin.
+fn encode_from_bytes(
+    source: InputSource,
+    output_opts: OutputOptions,
+    opts: &BatchOptions,
+) -> Result<()> {
+    let bytes = match source {
+        InputSource::Value(hex) => derive::parse_hex16(&hex),
+        source => {
+            let mut data = Vec::new();
+            source
+                .reader()?
+                .read_to_end(&mut data)
+                .context("Failed to read bytes")?;
+            rawbytes::parse(&data)
+        }
+    };
+    let bytes = match bytes {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    let b58 = alphabet::encode(uuid::Uuid::from_bytes(bytes));
+    write_output(output_opts, opts, true, |out| {
+        out.record(conversion_record(&hex, b58, "b58uuid", false))
+    })
+}
+
+/// Run `emit` against a writer for stdout, or for `--output` (uncolored,
+/// asking before replacing it).
+fn write_output<F>(
+    output_opts: OutputOptions,
+    opts: &BatchOptions,
+    single: bool,
+    emit: F,
+) -> Result<()>
+where
+    F: FnOnce(&mut dyn OutputWriter) -> Result<()>,
+{
+    match &opts.output {
+        Some(path) => {
+            // Files never get color
+            colored::control::set_override(false);
+            let path = Path::new(path);
+            confirm_overwrite(&[path], opts.yes)?;
+            write_to_file(path, |out| {
+                write_records(output_opts, Box::new(out), single, emit)
+            })
+        }
+        None => write_records(output_opts, output::stdout(), single, emit),
+    }
+}
+
+/// Encode or decode a single value or a batch, writing results to stdout or
+/// `--output`.
+fn convert(
+    source: InputSource,
+    direction: Direction,
+    output_opts: OutputOptions,
+    opts: &BatchOptions,
+) -> Result<()> {
+    if let (true, Some(path)) = (opts.resume, &opts.output) {
+        return convert_resumable(source, direction, output_opts, opts, Path::new(path));
+    }
+    if opts.csv || opts.where_filter.is_some() {
+        return convert_csv(source, direction, output_opts, opts);
+    }
+    if opts.serve {
+        return convert_serve(direction, output_opts, opts);
+    }
+
+    let single = matches!(source, InputSource::Value(_));
+    let emit = |out: &mut dyn OutputWriter| match source {
+        InputSource::Value(value) => {
+            convert_single(&value, direction, output_opts.format, opts, out)
+        }
+        source => {
+            let size = source.size();
+            process_lines(
+                source.sourced_lines(&opts.input)?,
+                size,
+                direction,
+                opts,
+                None,
+                out,
+            )
+        }
+    };
+    let write = |out: Box<dyn Write + '_>, single: bool| {
+        let mut writer: Box<dyn OutputWriter> = match (opts.map_format, &opts.prefix_map) {
+            (Some(mapping::MapFormat::Json), _) => {
+                let out = output::raw_writer(output_opts, out);
+                Box::new(mapping::MappingWriter::new(out, direction.is_encode()))
+            }
+            (None, Some(dir)) => Box::new(prefixmap::PrefixMapWriter::new(dir, opts.yes)?),
+            (None, None) => output::writer(output_opts, out, single),
+        };
+        if let Some(size) = opts.dedupe_window {
+            writer = Box::new(dedupe::DedupeWindowWriter::new(writer, size));
+        }
+        if opts.unique && !opts.sort {
+            writer = Box::new(dedupe::UniqueWriter::new(writer));
+        }
+        if opts.sort {
+            writer = Box::new(sort::SortingWriter::new(
+                writer,
+                opts.unique,
+                opts.max_memory,
+            ));
+        }
+        emit(&mut *writer)?;
+        opts.timed(profile::Phase::Write, || writer.finish())
+    };
+    match &opts.output {
+        Some(path) => {
+            // Files never get color
+            colored::control::set_override(false);
+            let path = Path::new(path);
+            confirm_overwrite(&[path], opts.yes)?;
+            // A stale checkpoint would not match the file written now
+            let _ = fs::remove_file(resume::sidecar_for(path));
+            write_to_file(path, |out| write(Box::new(out), single))
+        }
+        None => write(output::stdout(), single),
+    }
+}
+
+/// What the `--serve` loop waits on.
+enum ServeEvent {
+    Line(Result<String>),
+    /// Stdin is closed
+    End,
+    Interrupt,
+}
+
+/// `--serve`: convert stdin line by line for a long-lived caller, flushing
+/// each result. Every sentinel line closes the current batch (finishing its
+/// writer, so a JSON batch is one array) and is echoed back so the caller
+/// knows the batch is complete. Stdin is read on its own thread so Ctrl-C
+/// is noticed while waiting for input; it ends the run after finishing the
+/// open batch, like end of input does.
+fn convert_serve(
+    direction: Direction,
+    output_opts: OutputOptions,
+    opts: &BatchOptions,
+) -> Result<()> {
+    if output_opts.format == Format::BinaryPairs {
+        bail!("--serve delimits batches with a text line and cannot use --format binary-pairs");
+    }
+    let (tx, rx) = mpsc::channel();
+    let interrupt = tx.clone();
+    ctrlc::set_handler(move || {
+        let _ = interrupt.send(ServeEvent::Interrupt);
+    })?;
+    thread::spawn(move || {
+        for line in input::read_lines(io::stdin().lock(), "stdin") {
+            if tx.send(ServeEvent::Line(line)).is_err() {
+                return;
+            }
+        }
+        let _ = tx.send(ServeEvent::End);
+    });
+
+    let sentinel = opts.sentinel.as_deref().unwrap_or("");
+    let new_batch = || output::writer(output_opts, Box::new(io::stdout()), false);
+    let mut stats = BatchStats::default();
+    let mut batch: Option<Box<dyn OutputWriter>> = None;
+    while let Ok(ServeEvent::Line(line)) = rx.recv() {
+        let line = line?;
+        if line.trim() == sentinel {
+            batch.take().unwrap_or_else(new_batch).finish()?;
+            let mut out = output::raw_writer(output_opts, Box::new(io::stdout()));
+            writeln!(out, "{}", sentinel)?;
+            out.flush()?;
+            continue;
+        }
+        let out = batch.get_or_insert_with(new_batch);
+        convert_line(
+            "stdin", &line, direction, opts, None, &mut stats, &mut **out,
+        )?;
+        out.flush()?;
+    }
+    if let Some(mut out) = batch {
+        out.finish()?;
+    }
+    if opts.stats {
+        stats.report();
+    }
+    if opts.error_summary {
+        stats.report_errors();
+    }
+    Ok(())
+}
+
+/// Batch conversion of a CSV file with `--csv` or `--where`, writing the CSV
+/// back out with the column converted (in matching rows only, with
+/// `--where`).
+fn convert_csv(
+    source: InputSource,
+    direction: Direction,
+    output_opts: OutputOptions,
+    opts: &BatchOptions,
+) -> Result<()> {
+    let flag = if opts.csv { "--csv" } else { "--where" };
+    let path = match &source {
+        InputSource::File(path) => Some(path.as_str()),
+        _ => None,
+    };
+    if !opts.csv && opts.input.format.resolve(path) != InputFormat::Csv {
+        bail!("--where needs CSV input (a .csv file, --input-format csv or --csv)");
+    }
+    if !matches!(output_opts.format, Format::Text | Format::Csv) {
+        bail!(
+            "{} writes the input CSV back out and takes no --format",
+            flag
+        );
+    }
+    let input = source.reader()?;
+    let rows = selective::Rows {
+        column: opts.column.as_deref().or(opts.input.field.as_deref()),
+        filter: opts.where_filter.as_ref(),
+        drop_failed: opts.strict,
+    };
+    let convert = |value: &str| {
+        direction.convert(value, opts).map_err(|e| {
+            opts.note_failure();
+            errors::describe(&e)
+        })
+    };
+    let counts = match &opts.output {
+        Some(path) => {
+            let path = Path::new(path);
+            confirm_overwrite(&[path], opts.yes)?;
+            let mut counts = None;
+            write_to_file(path, |out| {
+                let mut out = output::raw_writer(output_opts, Box::new(out));
+                counts = Some(selective::rewrite_rows(input, &mut out, &rows, convert)?);
+                Ok(())
+            })?;
+            counts.unwrap_or_default()
+        }
+        None => {
+            let mut out = output::raw_writer(output_opts, Box::new(io::stdout().lock()));
+            selective::rewrite_rows(input, &mut out, &rows, convert)?
+        }
+    };
+    if opts.stats {
+        counts.report();
+    }
+    Ok(())
+}
+
+/// Batch conversion into `path` with `--resume`, continuing from the last
+/// checkpoint of an interrupted run.
+fn convert_resumable(
+    source: InputSource,
+    direction: Direction,
+    output_opts: OutputOptions,
+    opts: &BatchOptions,
+    path: &Path,
+) -> Result<()> {
+    // Resuming appends to the file, so the format must not buffer records or
+    // write a header
+    if !matches!(output_opts.format, Format::Text | Format::Ndjson) {
+        bail!("--resume only supports --format text or ndjson");
+    }
+    colored::control::set_override(false);
+    if !resume::sidecar_for(path).exists() {
+        // Without a checkpoint there is nothing to resume and the file is
+        // started afresh
+        confirm_overwrite(&[path], opts.yes)?;
+    }
+    let size = source.size();
+    let lines = source.sourced_lines(&opts.input)?;
+    let (mut progress, file) = resume::Progress::open(path)?;
+    write_records(output_opts, Box::new(file), false, |out| {
+        process_lines(lines, size, direction, opts, Some(&mut progress), out)
+    })?;
+    progress.complete()
+}
+
+/// Result of one conversion; `format` names the form `output` is in.
+fn conversion_record(input: &str, output: String, format: &str, annotate_savings: bool) -> Record {
+    if !annotate_savings {
+        return Record::new(output.green().to_string())
+            .field("input", input)
+            .field("output", output)
+            .field("format", format);
+    }
+    let saved = input.len() as i64 - output.len() as i64;
+    let saved_vs_binary = 16 - output.len() as i64;
+    let note = format!(
+        "({} → {} chars, {} bytes saved; {} over 16-byte binary)",
+        input.len(),
+        output.len(),
+        saved,
+        -saved_vs_binary
+    );
+    Record::new(format!("{}  {}", output.green(), note.dimmed()))
+        .field("input", input)
+        .field("output", output)
+        .field("format", format)
+        .field("saved_bytes", saved)
+        .field("saved_vs_binary", saved_vs_binary)
+}
+
+/// Every common textual form of a decoded UUID, one labeled line each.
+fn forms_record(input: &str, uuid: &str) -> Record {
+    let value = uuid::Uuid::parse_str(uuid).map_or(0, |uuid| uuid.as_u128());
+    let forms = [
+        ("canonical", "Canonical", uuid.to_string()),
+        ("uppercase", "Uppercase", uuid.to_uppercase()),
+        ("braced", "Braced", format!("{{{}}}", uuid)),
+        ("hex", "Hex", uuid.replace('-', "")),
+        ("integer", "Integer", value.to_string()),
+    ];
+    let text: Vec<String> = forms
+        .iter()
+        .map(|(_, label, form)| format!("{:<10} {}", format!("{}:", label), form.green()))
+        .collect();
+    forms.into_iter().fold(
+        Record::new(text.join("\n")).field("input", input),
+        |record, (name, _, form)| record.field(name, form),
+    )
+}
+
+fn convert_single(
+    value: &str,
+    direction: Direction,
+    format: Format,
+    opts: &BatchOptions,
+    out: &mut dyn OutputWriter,
+) -> Result<()> {
+    let value = input::trim(value);
+    match direction.convert(value, opts) {
+        Ok(output) => out.record(opts.record(direction, value, output)),
+        Err(e) => {
+            // JSON output reports the failure in-band, as an error object
+            if matches!(format, Format::Json | Format::Ndjson) {
+                out.error(value, &errors::describe(&e))?;
+                out.finish()?;
+            } else {
+                eprintln!("{} {}", "Error:".red().bold(), errors::describe(&e));
+            }
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Describe the first non-ASCII character in `value`, if any, as
+/// `b58uuid_cli::decode` does for Base58 input. Copy-pasted B58UUIDs
+/// sometimes carry Unicode look-alikes (e.g. Cyrillic 'а' for Latin 'a') that
+/// are otherwise reported as a confusing generic failure.
+fn non_ascii_message(value: &str) -> Option<String> {
+    value
+        .chars()
+        .enumerate()
+        .find(|(_, ch)| !ch.is_ascii())
+        .map(|(position, ch)| {
+            format!(
+                "non-ASCII character U+{:04X} at position {}; Base58 is ASCII-only",
+                ch as u32, position
+            )
+        })
+}
+
+fn decode_b58uuid(value: &str) -> Result<String, B58Error> {
+    alphabet::decode_to_uuid(value)
+}
+
+/// The case of every letter in `value`, if they share one and there are
+/// enough of them for that to be telling. Base58 is case-sensitive, so a
+/// B58UUID that went through something case-insensitive (a DNS name, a
+/// lowercasing database column) still decodes, to another UUID; a genuine
+/// one has letters of only one case about once in 80,000. Short trimmed
+/// values, mostly digits, are too often single-case by chance.
+fn single_case(value: &str) -> Option<&'static str> {
+    const MIN_LETTERS: usize = 10;
+    let lower = value.chars().filter(char::is_ascii_lowercase).count();
+    let upper = value.chars().filter(char::is_ascii_uppercase).count();
+    match (lower, upper) {
+        (lower, 0) if lower >= MIN_LETTERS => Some("lower"),
+        (0, upper) if upper >= MIN_LETTERS => Some("upper"),
+        _ => None,
+    }
+}
+
+/// `decode_b58uuid`, warning about (or under `--strict-b58` rejecting) a
+/// value that may have been case-folded, and under `--strict-length`
+/// rejecting the trimmed form. Nothing is ever changed to make a value
+/// decode.
+fn decode_checked(value: &str, opts: &BatchOptions) -> Result<String, B58Error> {
+    let uuid = decode_b58uuid(value)?;
+    let got = value.chars().count();
+    if opts.strict_length && got != 22 {
+        return Err(B58Error::WrongLength { expected: 22, got });
+    }
+    if let Some(case) = single_case(value) {
+        let hint = format!(
+            "all {} case; B58UUIDs are case-sensitive, and a case-folded one decodes to a different UUID",
+            case
+        );
+        if opts.strict_b58 {
+            return Err(B58Error::InvalidBase58(format!("{} (--strict-b58)", hint)));
+        }
+        if !errors::quiet() {
+            eprintln!("{} {} is {}", "Warning:".yellow().bold(), value, hint);
+        }
+    }
+    Ok(uuid)
+}
+
+/// Whether `encode` keeps the leading zero digits of a B58UUID.
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ZeroHandling {
+    /// Always 22 characters: leading zero digits are written as '1'
+    Pad,
+    /// Drop leading '1's, as encoders that treat the UUID as a plain number
+    /// do (at least one character is kept)
+    Trim,
+}
+
+/// `b58` without its leading zero digits, keeping one for the nil UUID.
+fn trim_zeros(b58: &str) -> String {
+    let zero = alphabet::get().digits()[0] as char;
+    let trimmed = b58.trim_start_matches(zero);
+    if trimmed.is_empty() {
+        zero.to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// `--color`.
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+/// Conversion applied to each line of a batch run.
+#[derive(Clone, Copy)]
+enum Direction {
+    Encode,
+    /// Encode without the leading '1' padding
+    EncodeTrimmed,
+    /// Encode to the Base58Check form
+    EncodeCheck,
+    Decode,
+    /// Decode to the 16 UUID bytes in hex
+    DecodeBytes,
+    /// Decode what decodes, encode the rest; or convert everything to one
+    /// form
+    Transcode(Option<Form>),
+    /// Decode from one encoding (or UUID/B58UUID, detected) and encode in
+    /// another
+    Recode(Option<encoding::Encoding>, encoding::Encoding),
+}
+
+/// Target form for `transcode --to`.
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum Form {
+    Uuid,
+    B58uuid,
+}
+
+impl Direction {
+    fn is_encode(self) -> bool {
+        matches!(
+            self,
+            Direction::Encode | Direction::EncodeTrimmed | Direction::EncodeCheck
+        )
+    }
+
+    /// Name of the form `output` is in, for the `format` field.
+    fn target(self, output: &str) -> &'static str {
+        match self {
+            Direction::Encode | Direction::EncodeTrimmed => "b58uuid",
+            Direction::EncodeCheck => "b58uuid-check",
+            Direction::Decode => "uuid",
+            Direction::DecodeBytes => "hex",
+            Direction::Transcode(_) if output.contains('-') => "uuid",
+            Direction::Transcode(_) => "b58uuid",
+            Direction::Recode(_, to) => to.name(),
+        }
+    }
+
+    /// Convert `value`, wrapping a UUID result as a `urn:uuid:` URN under
+    /// `--urn`.
+    fn convert(self, value: &str, opts: &BatchOptions) -> Result<String, B58Error> {
+        let output = self.convert_form(value, opts)?;
+        if opts.urn && self.target(&output) == "uuid" {
+            return Ok(format!("urn:uuid:{}", output));
+        }
+        Ok(output)
+    }
+
+    fn convert_form(self, value: &str, opts: &BatchOptions) -> Result<String, B58Error> {
+        match self {
+            Direction::Transcode(to) => return transcode(value, to, opts),
+            Direction::Recode(from, to) => return encoding::recode(value, from, to),
+            Direction::DecodeBytes => {
+                return Direction::Decode
+                    .convert_form(value, opts)
+                    .map(|uuid| uuid.replace('-', ""))
+            }
+            _ => {}
+        }
+        let normalized;
+        let value = if self.is_encode() && opts.unicode_normalize {
+            normalized = value.nfkc().collect::<String>();
+            normalized.as_str()
+        } else {
+            value
+        };
+        if self.is_encode() && !opts.tolerant_hyphens {
+            b58uuid_cli::check_hyphens(value)?;
+        }
+        let mixed = opts.byte_order == byteorder::ByteOrder::Mixed;
+        let swapped;
+        let value = if self.is_encode() && mixed {
+            swapped = byteorder::swap_fields(value)?;
+            swapped.as_str()
+        } else {
+            value
+        };
+        match self {
+            Direction::Encode => alphabet::encode_uuid(value),
+            Direction::EncodeTrimmed => alphabet::encode_uuid(value).map(|b58| trim_zeros(&b58)),
+            Direction::EncodeCheck => check::encode_uuid(value),
+            Direction::Decode if mixed => decode_checked(value, opts)
+                .and_then(|uuid| byteorder::swap_fields(&uuid))
+                .map(|uuid| output::uuid_case(&uuid)),
+            Direction::Decode => decode_checked(value, opts).map(|uuid| output::uuid_case(&uuid)),
+            Direction::DecodeBytes | Direction::Transcode(_) | Direction::Recode(..) => {
+                unreachable!("handled above")
+            }
+        }
+    }
+
+    /// UUID version of a successful conversion, read from whichever side of
+    /// the conversion is the UUID.
+    fn version(self, input: &str, output: &str) -> Option<u8> {
+        let uuid_str = match self {
+            Direction::Encode | Direction::EncodeTrimmed | Direction::EncodeCheck => {
+                input.to_string()
+            }
+            Direction::Decode | Direction::DecodeBytes => output.to_string(),
+            Direction::Transcode(_) if output.contains('-') => output.to_string(),
+            Direction::Transcode(_) => decode_b58uuid(output).ok()?,
+            Direction::Recode(_, to) => to.decode(output).ok()?.to_string(),
+        };
+        uuid::Uuid::parse_str(&uuid_str.replace('-', ""))
+            .ok()
+            .map(|uuid| uuid.get_version_num() as u8)
+    }
+}
+
+/// `transcode`: decode `value` if it is a B58UUID, otherwise encode it as a
+/// UUID. With `to`, the result is always in that form: a value already in it
+/// is passed through the codec both ways, which normalizes it (e.g. pads a
+/// trimmed B58UUID). A value that is neither fails with the error of the form
+/// it looks closer to.
+fn transcode(value: &str, to: Option<Form>, opts: &BatchOptions) -> Result<String, B58Error> {
+    match decode_b58uuid(value) {
+        Ok(uuid) if to == Some(Form::B58uuid) => alphabet::encode_uuid(&uuid),
+        Ok(uuid) => Ok(uuid),
+        Err(decode_error) => {
+            let b58 = Direction::Encode
+                .convert(value, opts)
+                .map_err(|encode_error| {
+                    if looks_like_uuid(value) {
+                        encode_error
+                    } else {
+                        decode_error
+                    }
+                })?;
+            match to {
+                Some(Form::Uuid) => decode_b58uuid(&b58),
+                _ => Ok(b58),
+            }
+        }
+    }
+}
+
+/// Options for an encode/decode run; all but `annotate_savings`,
+/// `tolerant_hyphens`, `unicode_normalize`, `byte_order` and `all_forms` apply
+/// only to the stdin and file batch paths.
+struct BatchOptions {
+    exclude_versions: Vec<u8>,
+    stats: bool,
+    /// Phase timers for `--profile`.
+    profile: Option<Mutex<profile::Profile>>,
+    input: InputOptions,
+    /// Non-blank lines of the `--baseline` file, in order.
+    baseline: Option<Vec<String>>,
+    changed_only: bool,
+    output: Option<String>,
+    /// Overwrite an existing `output` without asking.
+    yes: bool,
+    resume: bool,
+    /// Draw a `--progress` bar (stderr is a terminal).
+    progress: bool,
+    error_summary: bool,
+    strict: bool,
+    /// Set when a value fails to convert, for `--strict`.
+    failed: AtomicBool,
+    /// Worker threads for conversion (`--jobs`).
+    jobs: usize,
+    with_source: bool,
+    /// Prefix errors with the file they came from, when reading several.
+    label_errors: bool,
+    map_format: Option<mapping::MapFormat>,
+    columns: Option<ColumnRange>,
+    keep_layout: bool,
+    sort: bool,
+    unique: bool,
+    max_memory: Option<u64>,
+    dedupe_window: Option<usize>,
+    where_filter: Option<WhereFilter>,
+    csv: bool,
+    column: Option<String>,
+    annotate_savings: bool,
+    serve: bool,
+    sentinel: Option<String>,
+    tolerant_hyphens: bool,
+    unicode_normalize: bool,
+    byte_order: byteorder::ByteOrder,
+    /// `--prefix-map` directory.
+    prefix_map: Option<PathBuf>,
+    all_forms: bool,
+    /// Write UUID results as `urn:uuid:` URNs.
+    urn: bool,
+    /// Reject single-case B58UUIDs instead of warning.
+    strict_b58: bool,
+    /// Reject B58UUIDs that are not 22 characters.
+    strict_length: bool,
+}
+
+impl BatchOptions {
+    fn new(
+        args: BatchArgs,
+        stats: bool,
+        profile: bool,
+        yes: bool,
+        input: InputOptions,
+    ) -> Result<Self> {
+        if args.max_memory.is_some() && !args.sort {
+            bail!("--max-memory requires --sort");
+        }
+        if args.column.is_some() && !args.csv && args.where_filter.is_none() {
+            bail!("--column needs --csv or --where");
+        }
+        if input.whitespace && (args.columns.is_some() || args.serve) {
+            bail!("--whitespace splits lines into tokens and cannot be combined with --columns or --serve");
+        }
+        let baseline = match args.baseline {
+            Some(path) => {
+                let content = fs::read_to_string(&path)
+                    .context(format!("Failed to read baseline: {}", path))?;
+                Some(
+                    content
+                        .lines()
+                        .map(str::trim)
+                        .filter(|line| !line.is_empty())
+                        .map(String::from)
+                        .collect(),
+                )
+            }
+            None => None,
+        };
+        Ok(BatchOptions {
+            exclude_versions: args.exclude_version,
+            stats,
+            profile: profile.then(profile::Profile::new),
+            input,
+            baseline,
+            changed_only: args.changed_only,
+            output: args.output,
+            yes,
+            resume: args.resume,
+            progress: args.progress && io::stderr().is_terminal(),
+            error_summary: args.error_summary,
+            strict: args.strict,
+            failed: AtomicBool::new(false),
+            jobs: args.jobs as usize,
+            with_source: args.with_source,
+            label_errors: false,
+            map_format: args.map_format,
+            columns: args.columns,
+            keep_layout: args.keep_layout,
+            sort: args.sort,
+            unique: args.unique,
+            max_memory: args.max_memory,
+            dedupe_window: args.dedupe_window.map(|n| n as usize),
+            where_filter: args.where_filter,
+            csv: args.csv,
+            column: args.column,
+            serve: args.serve,
+            sentinel: args.sentinel,
+            annotate_savings: false,
+            tolerant_hyphens: false,
+            unicode_normalize: false,
+            byte_order: byteorder::ByteOrder::Network,
+            prefix_map: None,
+            all_forms: false,
+            urn: false,
+            strict_b58: false,
+            strict_length: false,
+        })
+    }
+
+    fn note_failure(&self) {
+        self.failed.store(true, Ordering::Relaxed);
+    }
+
+    /// Under `--strict`, exit with code 1 if any value failed to convert.
+    fn exit_on_failure(&self) {
+        if self.strict && self.failed.load(Ordering::Relaxed) {
+            std::process::exit(1);
+        }
+    }
+
+    /// Run `f`, adding its duration to `phase` under `--profile`.
+    fn timed<T>(&self, phase: profile::Phase, f: impl FnOnce() -> T) -> T {
+        match &self.profile {
+            Some(profile) => {
+                let start = Instant::now();
+                let result = f();
+                profile
+                    .lock()
+                    .expect("profile lock")
+                    .add(phase, start.elapsed());
+                result
+            }
+            None => f(),
+        }
+    }
+
+    /// Print the `--profile` breakdown, as JSON when the output is JSON.
+    fn report_profile(&self, format: Format) {
+        if let Some(profile) = &self.profile {
+            profile
+                .lock()
+                .expect("profile lock")
+                .report(matches!(format, Format::Json | Format::Ndjson));
+        }
+    }
+
+    /// The record for one successful conversion.
+    fn record(&self, direction: Direction, input: &str, output: String) -> Record {
+        let format = direction.target(&output);
+        if self.map_format.is_some() {
+            conversion_record(input, output, format, false)
+        } else if self.all_forms {
+            forms_record(input, &output)
+        } else {
+            conversion_record(input, output, format, self.annotate_savings)
+        }
+    }
+}
+
+/// Line counts for a batch run; blank lines are not counted.
+#[derive(Default)]
+struct BatchStats {
+    processed: usize,
+    ok: usize,
+    errors: usize,
+    dropped: usize,
+    /// Baseline comparison counts, set when `--baseline` is given.
+    baseline: Option<BaselineCounts>,
+    /// Errors per reason, in order of first occurrence.
+    error_kinds: Vec<(&'static str, usize)>,
+}
+
+#[derive(Default)]
+struct BaselineCounts {
+    added: usize,
+    changed: usize,
+    unchanged: usize,
+}
+
+impl BatchStats {
+    fn report(&self) {
+        let mut summary = format!(
+            "Processed {} lines: {} ok, {} errors",
+            self.processed, self.ok, self.errors
+        );
+        if self.dropped > 0 {
+            summary.push_str(&format!(", {} dropped", self.dropped));
+        }
+        if let Some(counts) = &self.baseline {
+            summary.push_str(&format!(
+                "; vs baseline: {} added, {} changed, {} unchanged",
+                counts.added, counts.changed, counts.unchanged
+            ));
+        }
+        eprintln!("{}", summary);
+    }
+
+    fn count_error(&mut self, kind: &'static str) {
+        self.errors += 1;
+        match self.error_kinds.iter_mut().find(|(k, _)| *k == kind) {
+            Some((_, count)) => *count += 1,
+            None => self.error_kinds.push((kind, 1)),
+        }
+    }
+
+    fn report_errors(&self) {
+        if self.error_kinds.is_empty() {
+            eprintln!("No errors");
+            return;
+        }
+        eprintln!("Errors by reason:");
+        let width = self
+            .error_kinds
+            .iter()
+            .map(|(k, _)| k.len())
+            .max()
+            .unwrap_or(0);
+        for (kind, count) in &self.error_kinds {
+            eprintln!("  {:<width$}  {}", kind, count, width = width);
+        }
+    }
+}
+
+/// Short name of the reason a conversion failed, one for each `B58Error`
+/// variant.
+fn error_kind(error: &B58Error) -> &'static str {
+    use B58Error::*;
+    match error {
+        InvalidBase58Character { .. } => "invalid Base58 character",
+        NonAsciiBase58 { .. } => "non-ASCII Base58",
+        EmptyBase58 => "empty Base58",
+        TooManyLeadingOnes => "too many leading 1s",
+        InvalidBase58(_) => "invalid Base58",
+        WrongAlphabet { .. } => "wrong alphabet",
+        InvalidUuidFormat(_) => "invalid UUID format",
+        InvalidHex { .. } => "invalid hex digit",
+        NonAsciiUuid { .. } => "non-ASCII UUID",
+        MisplacedHyphens => "misplaced hyphens",
+        WrongLength { .. } => "invalid length",
+        Overflow => "overflow",
+        InvalidEncoding { .. } => "invalid encoding",
+    }
+}
+
+/// Convert every line of a batch, `size` bytes of input if known. With
+/// `progress`, lines handled by an earlier run are skipped and progress is
+/// checkpointed as lines complete.
+fn process_lines(
+    lines: SourcedLines,
+    size: Option<u64>,
+    direction: Direction,
+    opts: &BatchOptions,
+    mut progress: Option<&mut resume::Progress>,
+    out: &mut dyn OutputWriter,
+) -> Result<()> {
+    let mut stats = BatchStats {
+        baseline: opts.baseline.as_ref().map(|_| BaselineCounts::default()),
+        ..Default::default()
+    };
+    let skip = progress.as_ref().map_or(0, |p| p.skip);
+    let mut meter = opts.progress.then(|| meter::Meter::new(size));
+    let mut lines = lines.enumerate().skip(skip);
+    if opts.jobs > 1 {
+        loop {
+            let chunk = opts.timed(profile::Phase::Read, || {
+                lines
+                    .by_ref()
+                    .take(JOB_CHUNK_LINES * opts.jobs)
+                    .map(|(index, line)| line.map(|line| (index, line)))
+                    .collect::<Result<Vec<_>>>()
+            })?;
+            if chunk.is_empty() {
+                break;
+            }
+            let values: Vec<Option<&str>> = chunk
+                .iter()
+                .map(|(_, (_, line))| line_value(line, opts))
+                .collect();
+            let converted = opts.timed(profile::Phase::Convert, || {
+                convert_parallel(&values, direction, opts)
+            });
+            for ((index, (source, line)), converted) in chunk.iter().zip(converted) {
+                convert_line(source, line, direction, opts, converted, &mut stats, out)?;
+                if let Some(progress) = progress.as_mut() {
+                    progress.advance(index + 1)?;
+                }
+                if let Some(meter) = meter.as_mut() {
+                    meter.advance();
+                }
+            }
+        }
+    }
+    while let Some((index, line)) = opts.timed(profile::Phase::Read, || lines.next()) {
+        let (source, line) = line?;
+        convert_line(&source, &line, direction, opts, None, &mut stats, out)?;
+        if let Some(progress) = progress.as_mut() {
+            progress.advance(index + 1)?;
+        }
+        if let Some(meter) = meter.as_mut() {
+            meter.advance();
+        }
+    }
+    if let Some(meter) = meter.as_mut() {
+        meter.finish();
+    }
+    if opts.stats {
+        stats.report();
+    }
+    if opts.error_summary {
+        stats.report_errors();
+    }
+    Ok(())
+}
+
+/// Lines each `--jobs` worker converts per batch. Lines are read in chunks
+/// of this many per worker, converted in parallel and then written in order.
+const JOB_CHUNK_LINES: usize = 4096;
+
+/// The value `convert_line` converts in `line`, if there is one.
+fn line_value<'a>(line: &'a str, opts: &BatchOptions) -> Option<&'a str> {
+    let value = match opts.columns {
+        Some(columns) => columns.slice(line).ok()?,
+        None => line,
+    };
+    Some(input::trim(value)).filter(|value| !value.is_empty())
+}
+
+/// Convert `values` on `opts.jobs` threads, each taking one contiguous run
+/// of them, and return the results in the same order.
+fn convert_parallel(
+    values: &[Option<&str>],
+    direction: Direction,
+    opts: &BatchOptions,
+) -> Vec<Option<Result<String, B58Error>>> {
+    let per_job = values.len().div_ceil(opts.jobs).max(1);
+    thread::scope(|scope| {
+        let workers: Vec<_> = values
+            .chunks(per_job)
+            .map(|values| {
+                scope.spawn(move || {
+                    values
+                        .iter()
+                        .map(|value| value.map(|value| direction.convert(value, opts)))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("conversion worker panicked"))
+            .collect()
+    })
+}
+
+/// Convert one batch line read from `source`, updating `stats`. `converted`
+/// is the conversion of the line's value when a `--jobs` worker already did
+/// it.
+fn convert_line(
+    source: &str,
+    line: &str,
+    direction: Direction,
+    opts: &BatchOptions,
+    converted: Option<Result<String, B58Error>>,
+    stats: &mut BatchStats,
+    out: &mut dyn OutputWriter,
+) -> Result<()> {
+    let label = |value: &str| {
+        if opts.with_source || opts.label_errors {
+            format!("{}: {}", source, value)
+        } else {
+            value.to_string()
+        }
+    };
+    if line.trim().is_empty() {
+        return Ok(());
+    }
+    let value = match opts.columns.map(|columns| columns.slice(line)) {
+        Some(Ok(value)) => input::trim(value),
+        Some(Err(message)) => {
+            stats.processed += 1;
+            stats.count_error("column range");
+            opts.note_failure();
+            return out.error(&label(line), &errors::render("E_COLUMN_RANGE", message));
+        }
+        None => input::trim(line),
+    };
+    if value.is_empty() {
+        return Ok(());
+    }
+    stats.processed += 1;
+    let converted = converted
+        .unwrap_or_else(|| opts.timed(profile::Phase::Convert, || direction.convert(value, opts)));
+    match converted {
+        Ok(output) => {
+            if !opts.exclude_versions.is_empty() {
+                let version = direction.version(value, &output);
+                if version.is_some_and(|v| opts.exclude_versions.contains(&v)) {
+                    stats.dropped += 1;
+                    return Ok(());
+                }
+            }
+            // Outputs line up with the baseline by their own index, so
+            // errors and dropped lines do not shift the comparison
+            let index = stats.ok;
+            stats.ok += 1;
+            if let (Some(baseline), Some(counts)) = (&opts.baseline, &mut stats.baseline) {
+                match baseline.get(index) {
+                    None => counts.added += 1,
+                    Some(prior) if *prior != output => counts.changed += 1,
+                    Some(_) => {
+                        counts.unchanged += 1;
+                        if opts.changed_only {
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+            // Building the record counts as conversion: it formats the output
+            let record = opts.timed(profile::Phase::Convert, || {
+                let record = match opts.columns {
+                    Some(columns) if opts.keep_layout => {
+                        let layout = columns.splice(line, &output);
+                        opts.record(direction, value, output).with_text(layout)
+                    }
+                    _ => opts.record(direction, value, output),
+                };
+                if opts.with_source {
+                    record.with_source(source)
+                } else {
+                    record
+                }
+            });
+            opts.timed(profile::Phase::Write, || out.record(record))?;
+        }
+        Err(e) => {
+            stats.count_error(error_kind(&e));
+            opts.note_failure();
+            out.error(&label(value), &errors::describe(&e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Check before replacing any existing file in `paths`. On a terminal the
+/// user is asked (unless `yes`), and anything but `y` aborts; elsewhere the
+/// overwrite goes ahead so scripts are never blocked, but is noted on stderr.
+fn confirm_overwrite(paths: &[&Path], yes: bool) -> Result<()> {
+    let existing: Vec<&&Path> = paths.iter().filter(|path| path.exists()).collect();
+    if existing.is_empty() || yes {
+        return Ok(());
+    }
+    if !io::stdin().is_terminal() {
+        for path in existing {
+            eprintln!("Overwriting {}", path.display());
+        }
+        return Ok(());
+    }
+
+    let names: Vec<String> = existing.iter().map(|p| p.display().to_string()).collect();
+    eprint!("Overwrite {}? [y/N] ", names.join(", "));
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    if !matches!(answer.trim(), "y" | "Y" | "yes") {
+        bail!(
+            "Not overwriting {} (pass --yes to skip this check)",
+            names.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Run `write` against a buffered writer over a freshly created `path`.
+fn write_to_file<F>(path: &Path, write: F) -> Result<()>
+where
+    F: FnOnce(&mut dyn Write) -> Result<()>,
+{
+    let file = File::create(path).context(format!("Failed to create file: {}", path.display()))?;
+    let mut out = BufWriter::with_capacity(output::buffer_size(), file);
+    write(&mut out)?;
+    out.flush()
+        .context(format!("Failed to write file: {}", path.display()))
+}
+
+/// Run `write` against a temporary file next to `path`, then rename it over
+/// `path` once everything has been written and synced. Readers of `path` never
+/// observe a partially written file; on failure the temporary file is removed.
+fn write_atomically<F>(path: &Path, write: F) -> Result<()>
+where
+    F: FnOnce(&mut dyn Write) -> Result<()>,
+{
+    let tmp_path = temp_path_for(path);
+    let result = (|| -> Result<()> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&tmp_path)
+            .context(format!("Failed to create file: {}", tmp_path.display()))?;
+        let mut out = BufWriter::with_capacity(output::buffer_size(), file);
+        write(&mut out)?;
+        let file = out
+            .into_inner()
+            .map_err(|e| e.into_error())
+            .context(format!("Failed to write file: {}", tmp_path.display()))?;
+        file.sync_all()
+            .context(format!("Failed to sync file: {}", tmp_path.display()))?;
+        fs::rename(&tmp_path, path).context(format!(
+            "Failed to rename {} to {}",
+            tmp_path.display(),
+            path.display()
+        ))
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// Hidden temporary file in the same directory as `path`, so the final
+/// rename never crosses a filesystem boundary.
+fn temp_path_for(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.{}.tmp", name, std::process::id()))
+}
+
+/// Kind of a valid value, its UUID form and its B58UUID form.
+pub(crate) fn classify(value: &str) -> Option<(&'static str, String, String)> {
+    // The codec also takes trimmed B58UUIDs and hyphens anywhere, so "2" or
+    // a mangled UUID would otherwise count as valid
+    if alphabet::is_valid_b58uuid(value) {
+        if let Ok(uuid) = alphabet::decode_to_uuid(value) {
+            return Some(("b58uuid", uuid, value.to_string()));
+        }
+    }
+    if b58uuid_cli::is_valid_uuid(value) {
+        if let Ok(b58) = alphabet::encode_uuid(value) {
+            return Some(("uuid", value.to_string(), b58));
+        }
+    }
+    None
+}
+
+/// Version number of a UUID string, with what RFC 9562 defines it as.
+fn uuid_version(uuid: &str) -> Option<(u8, &'static str)> {
+    let version = uuid::Uuid::parse_str(uuid).ok()?.get_version_num() as u8;
+    let name = match version {
+        1 => "time-based",
+        2 => "DCE security",
+        3 => "name-based, MD5",
+        4 => "random",
+        5 => "name-based, SHA-1",
+        6 => "reordered time",
+        7 => "Unix time",
+        8 => "custom",
+        _ => "unknown",
+    };
+    Some((version, name))
+}
+
+/// Variant of a UUID string, by the layout its variant bits select; `None`
+/// for the nil UUID, whose all-zero bits select none in particular.
+fn uuid_variant(uuid: &str) -> Option<&'static str> {
+    let uuid = uuid::Uuid::parse_str(uuid)
+        .ok()
+        .filter(|uuid| !uuid.is_nil())?;
+    Some(match uuid.get_variant() {
+        uuid::Variant::NCS => "NCS",
+        uuid::Variant::RFC4122 => "RFC4122",
+        uuid::Variant::Microsoft => "Microsoft",
+        _ => "Future",
+    })
+}
+
+/// When a time-based UUID was minted, as an RFC 3339 timestamp: the 60-bit
+/// count of 100 ns intervals since 1582-10-15 of v1 and v6, or the 48-bit
+/// Unix milliseconds of v7. Other versions have no timestamp.
+fn uuid_timestamp(uuid: &str) -> Option<String> {
+    /// 100 ns intervals from the Gregorian reform to the Unix epoch.
+    const GREGORIAN_OFFSET: i64 = 122_192_928_000_000_000;
+    let uuid = uuid::Uuid::parse_str(uuid).ok()?;
+    let value = uuid.as_u128();
+    let (secs, nanos) = match uuid.get_version_num() {
+        1 | 6 => {
+            let fields = uuid.as_fields();
+            let (low, mid, high) = (fields.0 as i64, fields.1 as i64, fields.2 as i64);
+            let ticks = if uuid.get_version_num() == 1 {
+                (high & 0x0fff) << 48 | mid << 32 | low
+            } else {
+                low << 28 | mid << 12 | (high & 0x0fff)
+            } - GREGORIAN_OFFSET;
+            (
+                ticks.div_euclid(10_000_000),
+                ticks.rem_euclid(10_000_000) * 100,
+            )
+        }
+        7 => {
+            let millis = (value >> 80) as i64;
+            (millis / 1000, millis % 1000 * 1_000_000)
+        }
+        _ => return None,
+    };
+    Some(timestamp::format_rfc3339(secs, nanos as u32))
+}
+
+fn is_nil(uuid: &str) -> bool {
+    uuid::Uuid::parse_str(uuid).is_ok_and(|uuid| uuid.is_nil())
+}
+
+fn validation_record(text: String, value: &str, valid: Option<(&str, &str, &str)>) -> Record {
+    let record = Record::new(text).field("input", value);
+    match valid {
+        Some((kind, uuid, b58)) => record
+            .field("valid", true)
+            .field("type", kind)
+            .field("uuid", output::uuid_case(uuid))
+            .field("b58uuid", b58)
+            .field("version", uuid_version(uuid).map(|(version, _)| version))
+            .field("variant", uuid_variant(uuid))
+            .field("timestamp", uuid_timestamp(uuid))
+            .field("nil", is_nil(uuid)),
+        None => record
+            .field("valid", false)
+            .field("type", Value::Null)
+            .field("uuid", Value::Null)
+            .field("b58uuid", Value::Null)
+            .field("version", Value::Null)
+            .field("variant", Value::Null)
+            .field("timestamp", Value::Null)
+            .field("nil", Value::Null)
+            .failure(),
+    }
+}
+
+/// Validate each line, writing one status record per value (only the invalid
+/// ones with `quiet_success`). Returns whether every value was valid.
+fn validate_lines(
+    lines: Lines,
+    line_record: RecordFn,
+    stats: bool,
+    quiet_success: bool,
+    out: &mut dyn OutputWriter,
+) -> Result<bool> {
+    let mut counts = BatchStats::default();
+    for line in lines {
+        let line = line?;
+        let value = input::trim(&line);
+        if value.is_empty() {
+            continue;
+        }
+        counts.processed += 1;
+        let record = line_record(value);
+        if record.is_failure() {
+            counts.errors += 1;
+        } else {
+            counts.ok += 1;
+        }
+        if !quiet_success || record.is_failure() {
+            out.record(record)?;
+        }
+    }
+    if stats {
+        counts.report();
+    }
+    Ok(counts.errors == 0)
+}
+
+/// The case `--uppercase`/`--lowercase` (at most one, by clap) ask for.
+fn uuid_case(uppercase: bool, lowercase: bool) -> output::UuidCase {
+    match (uppercase, lowercase) {
+        (true, _) => output::UuidCase::Upper,
+        (_, true) => output::UuidCase::Lower,
+        _ => output::UuidCase::AsIs,
+    }
+}
+
+/// Whether an invalid `value` was more likely meant as a UUID than a B58UUID.
+fn looks_like_uuid(value: &str) -> bool {
+    value.contains('-') || (value.len() == 32 && value.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Why `value` is neither a UUID nor a B58UUID, judged by which one it looks
+/// closer to.
+fn invalid_reason(value: &str) -> String {
+    let error = if looks_like_uuid(value) {
+        alphabet::encode_uuid(value)
+            .and_then(|_| b58uuid_cli::check_hyphens(value))
+            .err()
+    } else {
+        // A value the codec decodes is only invalid for being trimmed or
+        // padded past 22 characters
+        Some(
+            decode_b58uuid(value)
+                .err()
+                .unwrap_or(B58Error::WrongLength {
+                    expected: 22,
+                    got: value.chars().count(),
+                }),
+        )
+    };
+    error.map_or_else(|| "Invalid format".to_string(), |e| e.to_string())
+}
+
+/// Validate every input line as a stream of compact JSON objects, one per
+/// line and flushed as it is written. Unlike `validate_lines`, blank lines
+/// are reported (as invalid) so line numbers always match the input. Returns
+/// whether every line was valid.
+fn validate_stream(lines: Lines, out: &mut dyn Write) -> Result<bool> {
+    let mut all_valid = true;
+    for (index, line) in lines.enumerate() {
+        let line = line?;
+        let value = line.trim();
+        let (kind, reason) = if value.is_empty() {
+            (None, Some("Empty line".to_string()))
+        } else {
+            match classify(value) {
+                Some(("b58uuid", _, _)) => (Some("b58"), None),
+                Some(_) => (Some("uuid"), None),
+                None => (None, Some(invalid_reason(value))),
+            }
+        };
+        all_valid &= kind.is_some();
+        let result = serde_json::json!({
+            "line": index + 1,
+            "input": value,
+            "valid": kind.is_some(),
+            "type": kind.unwrap_or("none"),
+            "reason": reason,
+        });
+        writeln!(out, "{}", result)?;
+        out.flush()?;
+    }
+    Ok(all_valid)
+}
+
+/// Builds the validation record for one value.
+type RecordFn = fn(&str) -> Record;
+
+/// One status line for a batch `validate`.
+fn line_record(value: &str) -> Record {
+    match classify(value) {
+        Some((kind, uuid, b58)) => {
+            let (shown, label) = if kind == "b58uuid" {
+                (value.to_string(), "B58UUID")
+            } else {
+                (output::uuid_case(value), "UUID")
+            };
+            let text = format!("{} {} ({})", "✓".green().bold(), shown, label);
+            validation_record(text, value, Some((kind, &uuid, &b58)))
+        }
+        None => {
+            let text = format!("{} {} - Invalid format", "✗".red().bold(), value);
+            validation_record(text, value, None)
+        }
+    }
+}
+
+/// Validate a single value, writing a detailed record (only if invalid with
+/// `quiet_success`). Returns whether it was valid.
+fn validate_value(
+    value: &str,
+    value_record: RecordFn,
+    quiet_success: bool,
+    out: &mut dyn OutputWriter,
+) -> Result<bool> {
+    let record = value_record(input::trim(value));
+    let valid = !record.is_failure();
+    if !quiet_success || !valid {
+        out.record(record)?;
+    }
+    Ok(valid)
+}
+
+/// The detailed report for a single `validate` value.
+fn value_record(value: &str) -> Record {
+    match classify(value) {
+        Some((kind, uuid, b58)) => {
+            let mut text = if kind == "b58uuid" {
+                format!(
+                    "{} Valid B58UUID\n  B58UUID: {}\n  UUID:    {}",
+                    "✓".green().bold(),
+                    value.cyan(),
+                    output::uuid_case(&uuid).cyan()
+                )
+            } else {
+                format!(
+                    "{} Valid UUID\n  UUID:    {}\n  B58UUID: {}",
+                    "✓".green().bold(),
+                    output::uuid_case(value).cyan(),
+                    b58.cyan()
+                )
+            };
+            if is_nil(&uuid) {
+                text.push_str("\n  Nil UUID: all bits zero, no version or variant");
+            } else if let Some((version, name)) = uuid_version(&uuid) {
+                text.push_str(&format!("\n  Version: {} ({})", version, name));
+                if let Some(variant) = uuid_variant(&uuid) {
+                    text.push_str(&format!("\n  Variant: {}", variant));
+                }
+                let minted = uuid_timestamp(&uuid);
+                text.push_str(&format!(
+                    "\n  Timestamp: {}",
+                    minted.as_deref().unwrap_or("n/a")
+                ));
+            }
+            validation_record(text, value, Some((kind, &uuid, &b58)))
+        }
+        None => {
+            let text = format!(
+                "{} Invalid format\n  Value: {}\n  Expected: UUID (36 chars) or B58UUID (22 chars)",
+                "✗".red().bold(),
+                value
+            );
+            validation_record(text, value, None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh directory holding `ids.txt` with `old` in it, if given.
+    fn atomic_target(name: &str, old: Option<&str>) -> (PathBuf, PathBuf) {
+        let dir =
+            std::env::temp_dir().join(format!("b58uuid-unit-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("ids.txt");
+        if let Some(old) = old {
+            fs::write(&path, old).unwrap();
+        }
+        (dir, path)
+    }
+
+    fn entries(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn failed_atomic_write_leaves_no_temp_file_and_no_partial_target() {
+        for old in [None, Some("old\n")] {
+            let (dir, path) = atomic_target("atomic-fail", old);
+            let result = write_atomically(&path, |out| {
+                writeln!(out, "partial")?;
+                bail!("generation failed")
+            });
+            assert!(result.is_err());
+            assert_eq!(fs::read_to_string(&path).ok().as_deref(), old);
+            let expected: Vec<&str> = old.map(|_| "ids.txt").into_iter().collect();
+            assert_eq!(entries(&dir), expected);
+            fs::remove_dir_all(dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn atomic_write_replaces_the_target_only_when_complete() {
+        let (dir, path) = atomic_target("atomic-ok", Some("old\n"));
+        write_atomically(&path, |out| {
+            // Until the rename the target still holds the old contents.
+            assert_eq!(fs::read_to_string(&path).unwrap(), "old\n");
+            assert!(temp_path_for(&path).exists());
+            writeln!(out, "new")?;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new\n");
+        assert_eq!(entries(&dir), ["ids.txt"]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn every_error_variant_has_its_own_kind() {
+        use b58uuid_cli::Alphabet;
+        let errors = [
+            B58Error::InvalidBase58Character { ch: '0', index: 0 },
+            B58Error::NonAsciiBase58 { ch: 'е', index: 0 },
+            B58Error::EmptyBase58,
+            B58Error::TooManyLeadingOnes,
+            B58Error::InvalidBase58(String::new()),
+            B58Error::WrongAlphabet {
+                expected: Alphabet::Bitcoin,
+                likely: Alphabet::Ripple,
+            },
+            B58Error::InvalidUuidFormat(String::new()),
+            B58Error::InvalidHex { index: 0 },
+            B58Error::NonAsciiUuid {
+                ch: '５', index: 0
+            },
+            B58Error::MisplacedHyphens,
+            B58Error::WrongLength {
+                expected: 32,
+                got: 8,
+            },
+            B58Error::Overflow,
+            B58Error::InvalidEncoding {
+                encoding: "Base32",
+                message: String::new(),
+            },
+        ];
+        let kinds: std::collections::HashSet<&str> = errors.iter().map(error_kind).collect();
+        assert_eq!(kinds.len(), errors.len());
+    }
+
+    #[test]
+    fn broken_pipe_is_found_under_context() {
+        let pipe = || io::Error::from(io::ErrorKind::BrokenPipe);
+        assert!(is_broken_pipe(&anyhow::Error::new(pipe())));
+        let wrapped = Err::<(), _>(pipe()).context("writing ids.txt").unwrap_err();
+        assert!(is_broken_pipe(&wrapped));
+        let other = anyhow::Error::new(io::Error::from(io::ErrorKind::PermissionDenied));
+        assert!(!is_broken_pipe(&other));
+        assert!(!is_broken_pipe(&anyhow::anyhow!("Broken pipe")));
+    }
+
+    #[test]
+    fn no_color_turns_auto_off() {
+        let ansi = || true;
+        assert_eq!(
+            color_override(ColorChoice::Auto, true, true, ansi),
+            Some(false)
+        );
+        assert_eq!(color_override(ColorChoice::Auto, false, true, ansi), None);
+        assert_eq!(
+            color_override(ColorChoice::Auto, false, false, ansi),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn explicit_choices_win_over_no_color() {
+        let ansi = || true;
+        assert_eq!(
+            color_override(ColorChoice::Always, true, false, ansi),
+            Some(true)
+        );
+        assert_eq!(
+            color_override(ColorChoice::Never, false, true, ansi),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn consoles_without_ansi_stay_plain() {
+        let ansi = || false;
+        assert_eq!(
+            color_override(ColorChoice::Auto, false, true, ansi),
+            Some(false)
+        );
+        assert_eq!(
+            color_override(ColorChoice::Always, false, true, ansi),
+            Some(false)
+        );
+    }
 }
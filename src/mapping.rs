@@ -0,0 +1,173 @@
+//! Versioned mapping documents for reversible migrations.
+//!
+//! A mapping records every value converted in a run together with what is
+//! needed to interpret it later: the tool version, the encoding and its
+//! alphabet (the digits of `--alphabet`), the direction of the conversion
+//! and when it was made.
+//!
+//! ```json
+//! {
+//!   "header": {
+//!     "tool": "b58uuid-cli",
+//!     "version": "1.0.0",
+//!     "encoding": "base58",
+//!     "alphabet": "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz",
+//!     "direction": "encode",
+//!     "created_at": "2026-01-01T00:00:00Z"
+//!   },
+//!   "entries": [
+//!     { "uuid": "550e8400-e29b-41d4-a716-446655440000", "b58uuid": "BWBeN28Vb7cMEx7Ym8AUzs" }
+//!   ]
+//! }
+//! ```
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::ValueEnum;
+use colored::*;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::input::Lines;
+use crate::output::{OutputWriter, Record};
+use crate::timestamp;
+
+const ENCODING: &str = "base58";
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MapFormat {
+    /// A JSON document with a header and the list of entries
+    Json,
+}
+
+/// Collects the conversions of a run and writes them as one mapping document
+/// when finished. `encode` is the direction of the run.
+pub struct MappingWriter<'a> {
+    out: Box<dyn Write + 'a>,
+    encode: bool,
+    entries: Vec<Value>,
+}
+
+impl<'a> MappingWriter<'a> {
+    pub fn new(out: Box<dyn Write + 'a>, encode: bool) -> Self {
+        MappingWriter {
+            out,
+            encode,
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl OutputWriter for MappingWriter<'_> {
+    fn record(&mut self, record: Record) -> Result<()> {
+        let (Some(input), Some(output)) = (record.get("input"), record.get("output")) else {
+            return Ok(());
+        };
+        let (uuid, b58) = if self.encode {
+            (input, output)
+        } else {
+            (output, input)
+        };
+        self.entries.push(json!({ "uuid": uuid, "b58uuid": b58 }));
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let doc = json!({
+            "header": {
+                "tool": env!("CARGO_PKG_NAME"),
+                "version": env!("CARGO_PKG_VERSION"),
+                "encoding": ENCODING,
+                "alphabet": std::str::from_utf8(crate::alphabet::get().digits())?,
+                "direction": if self.encode { "encode" } else { "decode" },
+                "created_at": timestamp::format_rfc3339(now.as_secs() as i64, now.subsec_nanos()),
+            },
+            "entries": std::mem::take(&mut self.entries),
+        });
+        writeln!(self.out, "{}", serde_json::to_string_pretty(&doc)?)?;
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Load the mapping at `path` as a lookup from each converted value back to
+/// its original. Fails if the mapping was made with a different encoding or
+/// alphabet, since its values would then mean something else.
+fn load_inverse(path: &str) -> Result<HashMap<String, String>> {
+    let content =
+        std::fs::read_to_string(path).context(format!("Failed to read file: {}", path))?;
+    let doc: Value =
+        serde_json::from_str(&content).context(format!("Invalid mapping: {}", path))?;
+    let header = &doc["header"];
+    if header["encoding"] != ENCODING {
+        bail!(
+            "Mapping {} uses encoding {}, expected {}",
+            path,
+            header["encoding"],
+            ENCODING
+        );
+    }
+    let digits = |alphabet: b58uuid_cli::Alphabet| std::str::from_utf8(alphabet.digits());
+    if header["alphabet"] != digits(crate::alphabet::get())? {
+        let made_with = b58uuid_cli::Alphabet::ALL
+            .into_iter()
+            .find(|&alphabet| digits(alphabet).is_ok_and(|digits| header["alphabet"] == digits));
+        match made_with {
+            Some(alphabet) => bail!(
+                "Mapping {} uses the {} alphabet (pass --alphabet {})",
+                path,
+                alphabet.name(),
+                alphabet.name()
+            ),
+            None => bail!("Mapping {} uses a different Base58 alphabet", path),
+        }
+    }
+    let (from, to) = match header["direction"].as_str() {
+        Some("encode") => ("b58uuid", "uuid"),
+        Some("decode") => ("uuid", "b58uuid"),
+        _ => bail!("Mapping {} has no valid direction", path),
+    };
+    let entries = doc["entries"]
+        .as_array()
+        .ok_or_else(|| anyhow!("Mapping {} has no entries", path))?;
+    entries
+        .iter()
+        .map(|entry| match (entry[from].as_str(), entry[to].as_str()) {
+            (Some(from), Some(to)) => Ok((from.to_string(), to.to_string())),
+            _ => bail!("Mapping {} has an incomplete entry: {}", path, entry),
+        })
+        .collect()
+}
+
+/// Undo the run recorded in the mapping at `path`: each value that run
+/// produced is replaced by the value it came from.
+pub fn reverse_map(path: &str, lines: Lines, out: &mut dyn OutputWriter) -> Result<bool> {
+    let inverse = load_inverse(path)?;
+    let mut all_found = true;
+    for line in lines {
+        let line = line?;
+        let value = line.trim();
+        if value.is_empty() {
+            continue;
+        }
+        match inverse.get(value) {
+            Some(original) => out.record(
+                Record::new(original.green().to_string())
+                    .field("input", value)
+                    .field("output", original.as_str()),
+            )?,
+            None => {
+                all_found = false;
+                out.error(
+                    value,
+                    &crate::errors::render("E_NOT_IN_MAPPING", "not in mapping"),
+                )?;
+            }
+        }
+    }
+    Ok(all_found)
+}
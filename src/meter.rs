@@ -0,0 +1,94 @@
+//! `--progress`: a progress bar on stderr for long batch runs.
+//!
+//! Input files are read through [`Counted`], which adds the bytes it hands
+//! out to a global count; the bar compares that count with the size of the
+//! input, so it moves with the read position whatever the input format. With
+//! no size to compare against (standard input) it shows the counts alone.
+//! It redraws at most ten times a second.
+
+use std::io::{self, Read, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+static BYTES_READ: AtomicU64 = AtomicU64::new(0);
+
+const REDRAW: Duration = Duration::from_millis(100);
+
+/// Characters of the bar itself, between the brackets.
+const WIDTH: usize = 30;
+
+/// A reader that counts the bytes read through it for the bar.
+pub struct Counted<R>(pub R);
+
+impl<R: Read> Read for Counted<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.0.read(buf)?;
+        BYTES_READ.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
+pub struct Meter {
+    /// Bytes of input in all, when known.
+    total: Option<u64>,
+    lines: u64,
+    start: Instant,
+    drawn: Instant,
+}
+
+impl Meter {
+    pub fn new(total: Option<u64>) -> Meter {
+        let now = Instant::now();
+        Meter {
+            total,
+            lines: 0,
+            start: now,
+            drawn: now,
+        }
+    }
+
+    /// Count one more line handled, redrawing if the bar is due.
+    pub fn advance(&mut self) {
+        self.lines += 1;
+        // Checking the clock on every line would cost more than the line
+        if self.lines.is_multiple_of(256) && self.drawn.elapsed() >= REDRAW {
+            self.draw();
+        }
+    }
+
+    /// Draw the final state and end the line, so later messages start on
+    /// their own.
+    pub fn finish(&mut self) {
+        self.draw();
+        eprintln!();
+    }
+
+    fn draw(&mut self) {
+        let read = BYTES_READ.load(Ordering::Relaxed);
+        let counts = format!(
+            "{} lines, {} in {:.1}s",
+            self.lines,
+            crate::bench::size(read as usize),
+            self.start.elapsed().as_secs_f64()
+        );
+        let line = match self.total {
+            Some(total) if total > 0 => {
+                let done = read.min(total) as f64 / total as f64;
+                let filled = (done * WIDTH as f64) as usize;
+                format!(
+                    "[{}{}] {:>3}% {}",
+                    "#".repeat(filled),
+                    "-".repeat(WIDTH - filled),
+                    (done * 100.0) as u32,
+                    counts
+                )
+            }
+            _ => counts,
+        };
+        // Clear to the end of the line, which a shorter redraw would leave
+        let mut stderr = io::stderr().lock();
+        let _ = write!(stderr, "\r{}\x1b[K", line);
+        let _ = stderr.flush();
+        self.drawn = Instant::now();
+    }
+}
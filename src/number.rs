@@ -0,0 +1,133 @@
+//! UUIDs as 128-bit integers.
+
+use anyhow::{anyhow, bail, Context, Result};
+use b58uuid_cli::B58Error;
+use colored::*;
+use uuid::Uuid;
+
+use crate::input::Lines;
+use crate::output::{OutputWriter, Record};
+
+/// Bases `--base` can print in.
+pub const BASES: [&str; 5] = ["2", "8", "10", "16", "58"];
+
+/// `value` in `base`, zero-padded to the digits `u128::MAX` needs in that
+/// base (128, 43, 39, 32 or 22), so every value has the same width the way
+/// B58UUIDs do. Base 58 uses the `--alphabet` digits, matching the B58UUID.
+pub fn to_base(value: u128, base: u32) -> String {
+    match base {
+        2 => format!("{:0128b}", value),
+        8 => format!("{:043o}", value),
+        10 => format!("{:039}", value),
+        16 => format!("{:032x}", value),
+        58 => crate::alphabet::encode(Uuid::from_u128(value)),
+        _ => unreachable!("base is one of BASES"),
+    }
+}
+
+/// Parse an integer that may have been copied from a debugger or hex editor:
+/// whitespace anywhere is ignored, and a `0x`/`0X` prefix selects hex
+/// (otherwise the digits are decimal). Fails if the value needs more than
+/// 128 bits.
+pub fn parse_u128(input: &str) -> Result<u128> {
+    let digits: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+    let (digits, radix) = match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => (hex, 16),
+        None => (digits.as_str(), 10),
+    };
+    if digits.is_empty() {
+        bail!("Empty number");
+    }
+    if let Some(bad) = digits.chars().find(|c| !c.is_digit(radix)) {
+        bail!("Invalid digit '{}' in base {} number", bad, radix);
+    }
+    u128::from_str_radix(digits, radix).context("Number exceeds 128 bits")
+}
+
+/// The 128-bit value of a UUID or a B58UUID in the `--alphabet`. A B58UUID
+/// that only decodes in another alphabet is not retried as a UUID.
+fn value_of(input: &str) -> Result<u128, B58Error> {
+    crate::alphabet::decode(input)
+        .or_else(|error| match error {
+            B58Error::WrongAlphabet { .. } => Err(error),
+            _ => crate::encoding::Encoding::Uuid.decode(input),
+        })
+        .map(|uuid| uuid.as_u128())
+}
+
+fn number_record(input: &str, value: u128, text: String) -> Record {
+    let uuid = Uuid::from_u128(value);
+    Record::new(text)
+        .field("input", input)
+        .field("integer", value.to_string())
+        .field("uuid", uuid.to_string())
+        .field("b58uuid", crate::alphabet::encode(uuid))
+}
+
+/// The record for one input: its integer value (in `base` if given) or,
+/// with `parse`, the B58UUID of an integer.
+fn convert(input: &str, parse: bool, base: Option<u32>) -> Result<Record> {
+    let value = if parse {
+        parse_u128(input)?
+    } else {
+        value_of(input).map_err(|error| match error {
+            B58Error::WrongAlphabet { .. } => anyhow!(crate::errors::describe(&error)),
+            _ => anyhow!("Invalid format: expected UUID or B58UUID"),
+        })?
+    };
+    let record = match base {
+        Some(base) => {
+            let digits = to_base(value, base);
+            number_record(input, value, digits.green().to_string())
+                .field("base", base)
+                .field("digits", digits)
+        }
+        None if parse => {
+            let text = crate::alphabet::encode(Uuid::from_u128(value));
+            number_record(input, value, text.green().to_string())
+        }
+        None => number_record(input, value, value.to_string().green().to_string()),
+    };
+    Ok(record)
+}
+
+/// Print the integer value of a UUID/B58UUID, or with `parse`, the B58UUID
+/// of an integer.
+pub fn number(
+    input: &str,
+    parse: bool,
+    base: Option<u32>,
+    out: &mut dyn OutputWriter,
+) -> Result<()> {
+    match convert(input.trim(), parse, base) {
+        Ok(record) => out.record(record),
+        Err(e) => {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// `number` for every line, reporting lines that do not convert as errors.
+pub fn number_lines(
+    lines: Lines,
+    parse: bool,
+    base: Option<u32>,
+    out: &mut dyn OutputWriter,
+) -> Result<()> {
+    for line in lines {
+        let line = line?;
+        let value = line.trim();
+        if value.is_empty() {
+            continue;
+        }
+        match convert(value, parse, base) {
+            Ok(record) => out.record(record)?,
+            Err(e) => out.error(value, &e.to_string())?,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,801 @@
+//! Output formats shared by all commands.
+//!
+//! Commands describe each result as a [`Record`] and hand it to an
+//! [`OutputWriter`] built once from `--format`; the writer decides how the
+//! record is rendered.
+
+use anyhow::{bail, Result};
+use clap::ValueEnum;
+use colored::*;
+use serde_json::{Map, Value};
+use std::io::{self, BufWriter, Write};
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Human-readable, colored output
+    Text,
+    /// A JSON object (single value) or array (batch)
+    Json,
+    /// One JSON object per line
+    Ndjson,
+    /// Comma-separated values with a header row
+    Csv,
+    /// Aligned columns with a header row
+    Table,
+    /// `encode` only: fixed 38-byte records of the 16 UUID bytes followed by
+    /// the 22 B58UUID characters, with no separators
+    BinaryPairs,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LineEnding {
+    /// `\n`
+    Lf,
+    /// `\r\n`, for Windows consumers
+    Crlf,
+    /// A NUL byte, for `xargs -0` (set by `--null`)
+    Nul,
+}
+
+/// `--uppercase`/`--lowercase`: the case `decode` and `validate` write UUID
+/// strings in.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum UuidCase {
+    /// As the codec produced them (lowercase) or the input had them
+    AsIs,
+    Lower,
+    Upper,
+}
+
+static UUID_CASE: AtomicU8 = AtomicU8::new(UuidCase::AsIs as u8);
+
+pub fn set_uuid_case(case: UuidCase) {
+    UUID_CASE.store(case as u8, Ordering::Relaxed);
+}
+
+/// `uuid` in the case chosen with `set_uuid_case`. B58UUIDs are
+/// case-sensitive and never go through this.
+pub fn uuid_case(uuid: &str) -> String {
+    match UUID_CASE.load(Ordering::Relaxed) {
+        case if case == UuidCase::Lower as u8 => uuid.to_ascii_lowercase(),
+        case if case == UuidCase::Upper as u8 => uuid.to_ascii_uppercase(),
+        _ => uuid.to_string(),
+    }
+}
+
+/// `--buffer-size` in bytes, or 0 when it was not given.
+static BUFFER_SIZE: AtomicUsize = AtomicUsize::new(0);
+
+/// Capacity of the buffer over output files without `--buffer-size`.
+const DEFAULT_BUFFER_SIZE: usize = 64 * 1024;
+
+pub fn set_buffer_size(bytes: Option<usize>) {
+    BUFFER_SIZE.store(bytes.unwrap_or(0), Ordering::Relaxed);
+}
+
+/// Capacity of the buffer between records and an output file.
+pub fn buffer_size() -> usize {
+    match BUFFER_SIZE.load(Ordering::Relaxed) {
+        0 => DEFAULT_BUFFER_SIZE,
+        bytes => bytes,
+    }
+}
+
+/// Standard output for batch and `generate` results. It is line-buffered,
+/// so results reach a pipe as they are written; with `--buffer-size` they
+/// are collected into chunks of that size instead.
+pub fn stdout() -> Box<dyn Write> {
+    match BUFFER_SIZE.load(Ordering::Relaxed) {
+        0 => Box::new(io::stdout().lock()),
+        bytes => Box::new(BufWriter::with_capacity(bytes, io::stdout().lock())),
+    }
+}
+
+/// Output settings taken from the global flags, shared by every writer.
+#[derive(Clone, Copy)]
+pub struct OutputOptions {
+    pub format: Format,
+    pub line_ending: LineEnding,
+    /// Flush after every record, for live consumers.
+    pub flush: bool,
+}
+
+/// One result of a command: named fields for the structured formats, plus
+/// its rendering in text mode.
+pub struct Record {
+    fields: Vec<(String, Value)>,
+    text: String,
+    failure: bool,
+}
+
+impl Record {
+    /// A record that reads as `text` in text mode.
+    pub fn new(text: impl Into<String>) -> Self {
+        Record {
+            fields: Vec::new(),
+            text: text.into(),
+            failure: false,
+        }
+    }
+
+    pub fn field(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.fields.push((name.into(), value.into()));
+        self
+    }
+
+    /// Replace the text-mode rendering.
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.text = text.into();
+        self
+    }
+
+    /// Tag the record with the file it came from: a leading `source` field,
+    /// and a `source: ` prefix in text mode.
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.fields.insert(0, ("source".to_string(), source.into()));
+        self.text = format!("{}: {}", source, self.text);
+        self
+    }
+
+    /// Mark the record as a failed check; text mode writes it to stderr.
+    pub fn failure(mut self) -> Self {
+        self.failure = true;
+        self
+    }
+
+    /// Value of the field `name`, if the record has one.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value)
+    }
+
+    pub fn is_failure(&self) -> bool {
+        self.failure
+    }
+
+    /// The text-mode rendering.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The whole record as one JSON value, for writers that park records on
+    /// disk; `from_json` reads it back.
+    pub fn to_json(&self) -> Value {
+        let fields: Vec<Value> = self
+            .fields
+            .iter()
+            .map(|(name, value)| Value::Array(vec![name.clone().into(), value.clone()]))
+            .collect();
+        serde_json::json!({ "text": self.text, "failure": self.failure, "fields": fields })
+    }
+
+    pub fn from_json(value: Value) -> Option<Record> {
+        let fields = value["fields"]
+            .as_array()?
+            .iter()
+            .map(|pair| Some((pair[0].as_str()?.to_string(), pair.get(1)?.clone())))
+            .collect::<Option<_>>()?;
+        Some(Record {
+            fields,
+            text: value["text"].as_str()?.to_string(),
+            failure: value["failure"].as_bool()?,
+        })
+    }
+
+    /// Rough number of bytes the record occupies in memory.
+    pub fn approx_size(&self) -> usize {
+        let fields: usize = self
+            .fields
+            .iter()
+            .map(|(name, value)| {
+                name.len()
+                    + match value {
+                        Value::String(s) => s.len(),
+                        _ => 16,
+                    }
+                    + 64
+            })
+            .sum();
+        std::mem::size_of::<Record>() + self.text.len() + fields
+    }
+
+    fn to_object(&self) -> Map<String, Value> {
+        self.fields
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect()
+    }
+
+    fn names(&self) -> Vec<String> {
+        self.fields.iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    /// Field values as plain cells for the tabular formats.
+    fn cells(&self) -> Vec<String> {
+        self.fields
+            .iter()
+            .map(|(_, value)| match value {
+                Value::Null => String::new(),
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            .collect()
+    }
+}
+
+pub trait OutputWriter {
+    fn record(&mut self, record: Record) -> Result<()>;
+
+    /// Report a value that could not be processed. Under `--error-format
+    /// code`, `message` is the error code and the value is left out; under
+    /// `--quiet` nothing is printed.
+    fn error(&mut self, input: &str, message: &str) -> Result<()> {
+        if crate::errors::quiet() {
+            return Ok(());
+        }
+        if crate::errors::codes() {
+            eprintln!("{} {}", "Error:".red().bold(), message);
+        } else {
+            eprintln!("{} {} - {}", "Error:".red().bold(), input, message);
+        }
+        Ok(())
+    }
+
+    /// Push records written so far to the output, for streams that must be
+    /// seen as they happen. Formats that buffer until `finish` ignore it.
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Flush anything buffered; called once after the last record.
+    fn finish(&mut self) -> Result<()>;
+}
+
+/// Build the writer for `opts.format` over `out`. `single` is set when the
+/// command was given one value on the command line, so JSON emits an object
+/// rather than an array.
+pub fn writer<'a>(
+    opts: OutputOptions,
+    out: Box<dyn Write + 'a>,
+    single: bool,
+) -> Box<dyn OutputWriter + 'a> {
+    let writer: Box<dyn OutputWriter + 'a> = match opts.format {
+        // Binary records are never subject to line-ending translation
+        Format::BinaryPairs => Box::new(BinaryPairsWriter { out }),
+        // A NUL ends each whole record, so a multi-line one stays together
+        format if opts.line_ending == LineEnding::Nul => text_writer(format, out, "\0", single),
+        format => text_writer(format, raw_writer(opts, out), "\n", single),
+    };
+    if opts.flush {
+        Box::new(FlushingWriter { inner: writer })
+    } else {
+        writer
+    }
+}
+
+/// `terminator` ends each record of the formats with one record per line;
+/// a JSON document and a table are written as they are.
+fn text_writer<'a>(
+    format: Format,
+    out: Box<dyn Write + 'a>,
+    terminator: &'static str,
+    single: bool,
+) -> Box<dyn OutputWriter + 'a> {
+    match format {
+        Format::Text => Box::new(TextWriter { out, terminator }),
+        Format::Json => Box::new(JsonWriter {
+            out,
+            single,
+            records: Vec::new(),
+        }),
+        Format::Ndjson => Box::new(NdjsonWriter { out, terminator }),
+        Format::Csv => Box::new(CsvWriter {
+            out,
+            terminator,
+            header_written: false,
+        }),
+        Format::Table => Box::new(TableWriter {
+            out,
+            header: Vec::new(),
+            rows: Vec::new(),
+        }),
+        Format::BinaryPairs => unreachable!("binary-pairs has no text writer"),
+    }
+}
+
+/// Wrap `out` for commands that write free-form text rather than records,
+/// applying the line ending from `opts` to every line.
+pub fn raw_writer<'a>(opts: OutputOptions, out: Box<dyn Write + 'a>) -> Box<dyn Write + 'a> {
+    let ending: &'static [u8] = match opts.line_ending {
+        LineEnding::Lf => return out,
+        LineEnding::Crlf => b"\r\n",
+        LineEnding::Nul => b"\0",
+    };
+    Box::new(LineEndingWriter { inner: out, ending })
+}
+
+struct TextWriter<'a> {
+    out: Box<dyn Write + 'a>,
+    terminator: &'static str,
+}
+
+impl OutputWriter for TextWriter<'_> {
+    fn record(&mut self, record: Record) -> Result<()> {
+        if record.failure {
+            eprintln!("{}", record.text);
+        } else {
+            write!(self.out, "{}{}", record.text, self.terminator)?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// A failed value as it appears among the JSON results, in place of going
+/// to stderr. Under `--error-format code` the value is left out here too.
+fn error_object(input: &str, message: &str) -> Value {
+    if crate::errors::codes() {
+        serde_json::json!({ "error": message })
+    } else {
+        serde_json::json!({ "input": input, "error": message })
+    }
+}
+
+struct JsonWriter<'a> {
+    out: Box<dyn Write + 'a>,
+    single: bool,
+    records: Vec<Value>,
+}
+
+impl OutputWriter for JsonWriter<'_> {
+    fn record(&mut self, record: Record) -> Result<()> {
+        self.records.push(Value::Object(record.to_object()));
+        Ok(())
+    }
+
+    fn error(&mut self, input: &str, message: &str) -> Result<()> {
+        self.records.push(error_object(input, message));
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let doc = if self.single && self.records.len() == 1 {
+            self.records.remove(0)
+        } else {
+            Value::Array(std::mem::take(&mut self.records))
+        };
+        writeln!(self.out, "{}", serde_json::to_string_pretty(&doc)?)?;
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+struct NdjsonWriter<'a> {
+    out: Box<dyn Write + 'a>,
+    terminator: &'static str,
+}
+
+impl OutputWriter for NdjsonWriter<'_> {
+    fn record(&mut self, record: Record) -> Result<()> {
+        let line = serde_json::to_string(&Value::Object(record.to_object()))?;
+        write!(self.out, "{}{}", line, self.terminator)?;
+        Ok(())
+    }
+
+    fn error(&mut self, input: &str, message: &str) -> Result<()> {
+        let line = error_object(input, message);
+        write!(self.out, "{}{}", line, self.terminator)?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+struct CsvWriter<'a> {
+    out: Box<dyn Write + 'a>,
+    terminator: &'static str,
+    header_written: bool,
+}
+
+/// Quote a CSV cell when it contains a delimiter, quote or line break.
+fn csv_escape(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+impl OutputWriter for CsvWriter<'_> {
+    fn record(&mut self, record: Record) -> Result<()> {
+        if !self.header_written {
+            let header: Vec<String> = record.names().iter().map(|n| csv_escape(n)).collect();
+            write!(self.out, "{}{}", header.join(","), self.terminator)?;
+            self.header_written = true;
+        }
+        let row: Vec<String> = record.cells().iter().map(|c| csv_escape(c)).collect();
+        write!(self.out, "{}{}", row.join(","), self.terminator)?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Buffers every row so columns can be padded to the widest cell.
+struct TableWriter<'a> {
+    out: Box<dyn Write + 'a>,
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl OutputWriter for TableWriter<'_> {
+    fn record(&mut self, record: Record) -> Result<()> {
+        if self.header.is_empty() {
+            self.header = record.names().iter().map(|n| n.to_uppercase()).collect();
+        }
+        self.rows.push(record.cells());
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if self.header.is_empty() {
+            return Ok(());
+        }
+        let mut widths: Vec<usize> = self.header.iter().map(|h| h.chars().count()).collect();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let render = |cells: &[String]| {
+            let padded: Vec<String> = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &width)| format!("{:<width$}", cell, width = width))
+                .collect();
+            padded.join("  ").trim_end().to_string()
+        };
+        writeln!(self.out, "{}", render(&self.header))?;
+        let rule: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+        writeln!(self.out, "{}", rule.join("  "))?;
+        for row in &self.rows {
+            writeln!(self.out, "{}", render(row))?;
+        }
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Length of one `binary-pairs` record: 16 UUID bytes, then 22 ASCII bytes.
+pub const BINARY_PAIR_LEN: usize = 16 + 22;
+
+/// Writes each record's `output` B58UUID as a [`BINARY_PAIR_LEN`]-byte
+/// record: bytes 0..16 are the UUID in network (big-endian) order, bytes
+/// 16..38 the B58UUID in ASCII. Records follow each other with no header,
+/// padding or separator, so record `i` starts at byte `i * 38`.
+struct BinaryPairsWriter<'a> {
+    out: Box<dyn Write + 'a>,
+}
+
+impl OutputWriter for BinaryPairsWriter<'_> {
+    fn record(&mut self, record: Record) -> Result<()> {
+        let b58 = match record.get("output") {
+            Some(Value::String(b58)) if b58.len() == 22 && b58.is_ascii() => b58,
+            _ => bail!("--format binary-pairs needs a 22-character B58UUID for every record"),
+        };
+        let uuid = crate::alphabet::decode(b58)?;
+        let mut pair = [0u8; BINARY_PAIR_LEN];
+        pair[..16].copy_from_slice(uuid.as_bytes());
+        pair[16..].copy_from_slice(b58.as_bytes());
+        self.out.write_all(&pair)?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Flushes the wrapped writer after every record.
+struct FlushingWriter<'a> {
+    inner: Box<dyn OutputWriter + 'a>,
+}
+
+impl OutputWriter for FlushingWriter<'_> {
+    fn record(&mut self, record: Record) -> Result<()> {
+        self.inner.record(record)?;
+        self.inner.flush()
+    }
+
+    fn error(&mut self, input: &str, message: &str) -> Result<()> {
+        self.inner.error(input, message)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.inner.finish()
+    }
+}
+
+/// Rewrites every `\n` written through it as `ending`.
+struct LineEndingWriter<'a> {
+    inner: Box<dyn Write + 'a>,
+    ending: &'static [u8],
+}
+
+impl Write for LineEndingWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut lines = buf.split(|&b| b == b'\n');
+        if let Some(first) = lines.next() {
+            self.inner.write_all(first)?;
+        }
+        for line in lines {
+            self.inner.write_all(self.ending)?;
+            self.inner.write_all(line)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Fixtures shared by the tests of writers that wrap another one.
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// `(output, input)` pairs with repeated outputs, told apart by their
+    /// inputs, which count up in input order.
+    pub const PAIRS: [(&str, &str); 6] = [
+        ("c", "1"),
+        ("a", "2"),
+        ("b", "3"),
+        ("a", "4"),
+        ("c", "5"),
+        ("a", "6"),
+    ];
+
+    /// The record of `input` converted to `output`.
+    pub fn pair(output: &str, input: &str) -> Record {
+        Record::new(output)
+            .field("input", input)
+            .field("output", output)
+    }
+
+    /// Collects the `input` field of every record it is given; clones share
+    /// what was collected.
+    #[derive(Clone, Default)]
+    pub struct Collect(Rc<RefCell<Vec<String>>>);
+
+    impl Collect {
+        pub fn inputs(&self) -> Vec<String> {
+            self.0.borrow().clone()
+        }
+    }
+
+    impl OutputWriter for Collect {
+        fn record(&mut self, record: Record) -> Result<()> {
+            let input = record.get("input").and_then(Value::as_str).unwrap_or("");
+            self.0.borrow_mut().push(input.to_string());
+            Ok(())
+        }
+
+        fn finish(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Everything `records` render to in `format`, ending lines with
+    /// `line_ending`.
+    fn render(format: Format, line_ending: LineEnding, records: Vec<Record>) -> String {
+        let mut buffer = Vec::new();
+        let opts = OutputOptions {
+            format,
+            line_ending,
+            flush: false,
+        };
+        let mut out = writer(opts, Box::new(&mut buffer), false);
+        for record in records {
+            out.record(record).unwrap();
+        }
+        out.finish().unwrap();
+        drop(out);
+        String::from_utf8(buffer).unwrap()
+    }
+
+    fn block() -> Record {
+        Record::new("Input: a\nType: b").field("input", "a")
+    }
+
+    #[test]
+    fn nul_ends_each_record_once() {
+        assert_eq!(
+            render(Format::Text, LineEnding::Nul, vec![block(), block()]),
+            "Input: a\nType: b\0Input: a\nType: b\0"
+        );
+        assert_eq!(
+            render(Format::Ndjson, LineEnding::Nul, vec![block()]),
+            "{\"input\":\"a\"}\0"
+        );
+        let quoted = Record::new("").field("note", "two\nlines");
+        assert_eq!(
+            render(Format::Csv, LineEnding::Nul, vec![quoted]),
+            "note\0\"two\nlines\"\0"
+        );
+    }
+
+    #[test]
+    fn crlf_ends_every_line() {
+        assert_eq!(
+            render(Format::Text, LineEnding::Crlf, vec![block()]),
+            "Input: a\r\nType: b\r\n"
+        );
+    }
+
+    fn encoded() -> Record {
+        Record::new("BWBeN28Vb7cMEx7Ym8AUzs")
+            .field("input", "550e8400-e29b-41d4-a716-446655440000")
+            .field("output", "BWBeN28Vb7cMEx7Ym8AUzs")
+    }
+
+    /// What `format` writes for one record and for one failed value, each
+    /// in a single-value run.
+    fn render_single(format: Format, failed: bool) -> String {
+        let mut buffer = Vec::new();
+        let opts = OutputOptions {
+            format,
+            line_ending: LineEnding::Lf,
+            flush: false,
+        };
+        let mut out = writer(opts, Box::new(&mut buffer), true);
+        if failed {
+            out.error("bad", "Invalid Base58: Empty Base58 string")
+                .unwrap();
+        } else {
+            out.record(encoded()).unwrap();
+        }
+        out.finish().unwrap();
+        drop(out);
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn each_format_renders_a_record() {
+        assert_eq!(
+            render_single(Format::Text, false),
+            "BWBeN28Vb7cMEx7Ym8AUzs\n"
+        );
+        assert_eq!(
+            render_single(Format::Json, false),
+            "{\n  \"input\": \"550e8400-e29b-41d4-a716-446655440000\",\n  \"output\": \"BWBeN28Vb7cMEx7Ym8AUzs\"\n}\n"
+        );
+        assert_eq!(
+            render_single(Format::Ndjson, false),
+            "{\"input\":\"550e8400-e29b-41d4-a716-446655440000\",\"output\":\"BWBeN28Vb7cMEx7Ym8AUzs\"}\n"
+        );
+        assert_eq!(
+            render_single(Format::Csv, false),
+            "input,output\n550e8400-e29b-41d4-a716-446655440000,BWBeN28Vb7cMEx7Ym8AUzs\n"
+        );
+        assert_eq!(
+            render_single(Format::Table, false),
+            "INPUT                                 OUTPUT\n\
+             ------------------------------------  ----------------------\n\
+             550e8400-e29b-41d4-a716-446655440000  BWBeN28Vb7cMEx7Ym8AUzs\n"
+        );
+    }
+
+    #[test]
+    fn json_formats_report_errors_in_band() {
+        assert_eq!(
+            render_single(Format::Json, true),
+            "{\n  \"input\": \"bad\",\n  \"error\": \"Invalid Base58: Empty Base58 string\"\n}\n"
+        );
+        assert_eq!(
+            render_single(Format::Ndjson, true),
+            "{\"input\":\"bad\",\"error\":\"Invalid Base58: Empty Base58 string\"}\n"
+        );
+        // The others leave stdout to results and report on stderr
+        for format in [Format::Text, Format::Csv, Format::Table] {
+            assert_eq!(render_single(format, true), "");
+        }
+    }
+
+    #[test]
+    fn failed_checks_stay_out_of_text_output() {
+        let failed = Record::new("✗ bad").field("valid", false).failure();
+        assert_eq!(render(Format::Text, LineEnding::Lf, vec![failed]), "");
+        let failed = Record::new("✗ bad").field("valid", false).failure();
+        assert_eq!(
+            render(Format::Ndjson, LineEnding::Lf, vec![failed]),
+            "{\"valid\":false}\n"
+        );
+    }
+
+    /// A byte buffer shared with the test, standing in for a pipe.
+    #[derive(Clone, Default)]
+    struct Pipe(std::rc::Rc<std::cell::RefCell<Vec<u8>>>);
+
+    impl Write for Pipe {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// What reaches a pipe behind a large buffer after each of three
+    /// records, with `--flush` on or off.
+    fn seen_after_each_record(flush: bool) -> Vec<String> {
+        let pipe = Pipe::default();
+        let buffered = BufWriter::with_capacity(1 << 16, pipe.clone());
+        let opts = OutputOptions {
+            format: Format::Text,
+            line_ending: LineEnding::Lf,
+            flush,
+        };
+        let mut out = writer(opts, Box::new(buffered), false);
+        let mut seen = Vec::new();
+        for text in ["a", "b", "c"] {
+            out.record(Record::new(text)).unwrap();
+            seen.push(String::from_utf8(pipe.0.borrow().clone()).unwrap());
+        }
+        seen
+    }
+
+    #[test]
+    fn flush_pushes_out_every_record_as_it_is_written() {
+        assert_eq!(seen_after_each_record(true), ["a\n", "a\nb\n", "a\nb\nc\n"]);
+        assert_eq!(seen_after_each_record(false), ["", "", ""]);
+    }
+}
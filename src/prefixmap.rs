@@ -0,0 +1,108 @@
+//! `--prefix-map`: route each B58UUID to a file named after its first
+//! character, sharding the output in the same pass as the conversion.
+//!
+//! Bucket files (`<dir>/<char>.txt`) are created the first time a value for
+//! them arrives and are buffered independently. Base58 mixes upper and lower
+//! case, so on a case-insensitive filesystem `a.txt` and `A.txt` would be the
+//! same file; that is detected when the second one is opened and reported
+//! rather than letting the two buckets overwrite each other.
+
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::output::{OutputWriter, Record};
+use crate::sort::key;
+
+struct Bucket {
+    out: BufWriter<File>,
+    count: usize,
+}
+
+pub struct PrefixMapWriter {
+    dir: PathBuf,
+    /// Replace bucket files left by an earlier run without asking.
+    yes: bool,
+    buckets: BTreeMap<char, Bucket>,
+}
+
+impl PrefixMapWriter {
+    pub fn new(dir: &Path, yes: bool) -> Result<Self> {
+        fs::create_dir_all(dir)
+            .context(format!("Failed to create directory: {}", dir.display()))?;
+        Ok(PrefixMapWriter {
+            dir: dir.to_path_buf(),
+            yes,
+            buckets: BTreeMap::new(),
+        })
+    }
+
+    fn open(&self, prefix: char) -> Result<Bucket> {
+        let path = self.dir.join(format!("{}.txt", prefix));
+        if path.exists() {
+            let twin = self
+                .buckets
+                .keys()
+                .find(|other| other.eq_ignore_ascii_case(&prefix));
+            if let Some(twin) = twin {
+                bail!(
+                    "{} is the same file as {}.txt here (case-insensitive filesystem); --prefix-map needs a case-sensitive one",
+                    path.display(),
+                    twin
+                );
+            }
+            crate::confirm_overwrite(&[&path], self.yes)?;
+        }
+        let file =
+            File::create(&path).context(format!("Failed to create file: {}", path.display()))?;
+        Ok(Bucket {
+            out: BufWriter::new(file),
+            count: 0,
+        })
+    }
+}
+
+impl OutputWriter for PrefixMapWriter {
+    fn record(&mut self, record: Record) -> Result<()> {
+        let value = key(&record);
+        let Some(prefix) = value.chars().next() else {
+            return Ok(());
+        };
+        if !self.buckets.contains_key(&prefix) {
+            let bucket = self.open(prefix)?;
+            self.buckets.insert(prefix, bucket);
+        }
+        let bucket = self
+            .buckets
+            .get_mut(&prefix)
+            .expect("bucket was just opened");
+        writeln!(bucket.out, "{}", value)?;
+        bucket.count += 1;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        for bucket in self.buckets.values_mut() {
+            bucket.out.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Flush every bucket and report how many values each received.
+    fn finish(&mut self) -> Result<()> {
+        self.flush()?;
+        let total: usize = self.buckets.values().map(|bucket| bucket.count).sum();
+        eprintln!(
+            "Wrote {} values to {} files in {}",
+            total,
+            self.buckets.len(),
+            self.dir.display()
+        );
+        for (prefix, bucket) in &self.buckets {
+            eprintln!("  {}.txt  {}", prefix, bucket.count);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+//! `--profile`: a rough split of a batch run's wall time into reading input,
+//! converting values and writing output, to tell I/O-bound runs from
+//! CPU-bound ones.
+//!
+//! Timers wrap whole phases of the batch loop, so the split is coarse:
+//! "write" covers handing a record to the writer (and the final flush, where
+//! buffering formats do most of their work), and anything outside the three
+//! phases is reported as "other".
+
+use serde_json::json;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy)]
+pub enum Phase {
+    Read,
+    Convert,
+    Write,
+}
+
+pub struct Profile {
+    start: Instant,
+    read: Duration,
+    convert: Duration,
+    write: Duration,
+}
+
+impl Profile {
+    pub fn new() -> Mutex<Profile> {
+        Mutex::new(Profile {
+            start: Instant::now(),
+            read: Duration::ZERO,
+            convert: Duration::ZERO,
+            write: Duration::ZERO,
+        })
+    }
+
+    pub fn add(&mut self, phase: Phase, elapsed: Duration) {
+        match phase {
+            Phase::Read => self.read += elapsed,
+            Phase::Convert => self.convert += elapsed,
+            Phase::Write => self.write += elapsed,
+        }
+    }
+
+    /// Print the breakdown since `new` to stderr, as one JSON object when
+    /// `as_json` and as a small table otherwise.
+    pub fn report(&self, as_json: bool) {
+        let total = self.start.elapsed();
+        let other = total.saturating_sub(self.read + self.convert + self.write);
+        let phases = [
+            ("read", self.read),
+            ("convert", self.convert),
+            ("write", self.write),
+            ("other", other),
+        ];
+
+        if as_json {
+            let mut doc = json!({ "total_ms": ms(total) });
+            for (name, elapsed) in phases {
+                doc[format!("{}_ms", name)] = json!(ms(elapsed));
+            }
+            eprintln!("{}", doc);
+            return;
+        }
+        eprintln!("Profile: {:.3}s total", total.as_secs_f64());
+        for (name, elapsed) in phases {
+            let share = if total.is_zero() {
+                0.0
+            } else {
+                elapsed.as_secs_f64() / total.as_secs_f64() * 100.0
+            };
+            eprintln!(
+                "  {:<8} {:>8.3}s {:>5.1}%",
+                name,
+                elapsed.as_secs_f64(),
+                share
+            );
+        }
+    }
+}
+
+/// Milliseconds with microsecond precision.
+fn ms(elapsed: Duration) -> f64 {
+    (elapsed.as_micros() as f64) / 1000.0
+}
@@ -0,0 +1,24 @@
+//! `encode --from-bytes`: UUIDs that arrive as their 16 bytes (e.g. from a
+//! database driver) rather than as strings, encoded without a detour through
+//! the hyphenated text form.
+
+use anyhow::{bail, Result};
+
+/// The 16 bytes in `data`: exactly 16 raw bytes, or hex text of 32 digits
+/// (whitespace and a `0x` prefix are ignored). Anything else is an error, so
+/// a truncated or padded value is never encoded.
+pub fn parse(data: &[u8]) -> Result<[u8; 16]> {
+    if let Ok(bytes) = <[u8; 16]>::try_from(data) {
+        return Ok(bytes);
+    }
+    // Text made only of hex digits (and the prefix and spacing hex may
+    // carry) is taken for hex; anything else for raw bytes of the wrong size
+    let hex_chars = |c: char| c.is_ascii_hexdigit() || c.is_whitespace() || c == 'x' || c == 'X';
+    match std::str::from_utf8(data) {
+        Ok(text) if text.chars().all(hex_chars) => crate::derive::parse_hex16(text),
+        _ => bail!(
+            "Invalid length: expected 16 raw bytes or 32 hex digits, got {} bytes",
+            data.len()
+        ),
+    }
+}
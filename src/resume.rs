@@ -0,0 +1,155 @@
+//! Checkpointing for `--resume`, so a long batch run into `--output` can be
+//! restarted after a crash without redoing finished work.
+//!
+//! Progress lives in a `<output>.progress` sidecar holding the number of input
+//! lines consumed and the length of the output file at that point. Both are
+//! written only after the output has been flushed, so the output is always at
+//! least as long as the sidecar says. On restart the output is truncated back
+//! to the recorded length (dropping anything written after the last
+//! checkpoint) and that many input lines are skipped.
+//!
+//! This assumes the input is unchanged between runs: the sidecar records a
+//! position, not the content that was read.
+
+use anyhow::{bail, Context, Result};
+use std::cell::RefCell;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Input lines between checkpoints.
+const CHECKPOINT_INTERVAL: usize = 1000;
+
+/// Output file shared between the record writer and the checkpointing loop.
+#[derive(Clone)]
+pub struct SharedFile(Rc<RefCell<BufWriter<File>>>);
+
+impl Write for SharedFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.borrow_mut().flush()
+    }
+}
+
+pub struct Progress {
+    sidecar: PathBuf,
+    file: SharedFile,
+    /// Input lines already handled by earlier runs.
+    pub skip: usize,
+    since_checkpoint: usize,
+}
+
+/// Path of the progress sidecar for `output`.
+pub fn sidecar_for(output: &Path) -> PathBuf {
+    let mut name = output.as_os_str().to_owned();
+    name.push(".progress");
+    PathBuf::from(name)
+}
+
+impl Progress {
+    /// Open `output` for a resumable run, picking up from its sidecar if one
+    /// exists and starting a fresh file otherwise.
+    pub fn open(output: &Path) -> Result<(Progress, SharedFile)> {
+        let sidecar = sidecar_for(output);
+        let (skip, bytes) = match fs::read_to_string(&sidecar) {
+            Ok(content) => parse_sidecar(&content)
+                .context(format!("Invalid progress file: {}", sidecar.display()))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (0, 0),
+            Err(e) => return Err(e).context(format!("Failed to read file: {}", sidecar.display())),
+        };
+
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(skip == 0)
+            .open(output)
+            .context(format!("Failed to open file: {}", output.display()))?;
+        if skip > 0 {
+            let len = file.metadata()?.len();
+            if len < bytes {
+                bail!(
+                    "Cannot resume: {} is {} bytes but {} records {}",
+                    output.display(),
+                    len,
+                    sidecar.display(),
+                    bytes
+                );
+            }
+            file.set_len(bytes)?;
+            file.seek(SeekFrom::End(0))?;
+            eprintln!("Resuming after {} lines of input", skip);
+        }
+
+        let file = SharedFile(Rc::new(RefCell::new(BufWriter::with_capacity(
+            crate::output::buffer_size(),
+            file,
+        ))));
+        let progress = Progress {
+            sidecar,
+            file: file.clone(),
+            skip,
+            since_checkpoint: 0,
+        };
+        Ok((progress, file))
+    }
+
+    /// Note that input line `consumed` (counted from the start of the input,
+    /// including skipped lines) has been fully handled; every
+    /// `CHECKPOINT_INTERVAL` lines the output is flushed and the sidecar
+    /// updated.
+    pub fn advance(&mut self, consumed: usize) -> Result<()> {
+        self.since_checkpoint += 1;
+        if self.since_checkpoint >= CHECKPOINT_INTERVAL {
+            self.checkpoint(consumed)?;
+        }
+        Ok(())
+    }
+
+    fn checkpoint(&mut self, consumed: usize) -> Result<()> {
+        let bytes = {
+            let mut out = self.file.0.borrow_mut();
+            out.flush()?;
+            out.get_mut().stream_position()?
+        };
+        // Write through a temporary file so a crash never leaves a torn sidecar
+        let tmp = self.sidecar.with_extension("progress.tmp");
+        fs::write(&tmp, format!("lines={}\nbytes={}\n", consumed, bytes))
+            .context(format!("Failed to write file: {}", tmp.display()))?;
+        fs::rename(&tmp, &self.sidecar)
+            .context(format!("Failed to write file: {}", self.sidecar.display()))?;
+        self.since_checkpoint = 0;
+        Ok(())
+    }
+
+    /// The run completed: the sidecar is no longer needed.
+    pub fn complete(self) -> Result<()> {
+        self.file.0.borrow_mut().flush()?;
+        match fs::remove_file(&self.sidecar) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                Err(e).context(format!("Failed to remove file: {}", self.sidecar.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Read `lines=N` and `bytes=M` from a sidecar.
+fn parse_sidecar(content: &str) -> Result<(usize, u64)> {
+    let mut lines = None;
+    let mut bytes = None;
+    for line in content.lines() {
+        match line.split_once('=') {
+            Some(("lines", n)) => lines = Some(n.trim().parse()?),
+            Some(("bytes", n)) => bytes = Some(n.trim().parse()?),
+            _ => {}
+        }
+    }
+    match (lines, bytes) {
+        (Some(lines), Some(bytes)) => Ok((lines, bytes)),
+        _ => bail!("expected lines= and bytes= entries"),
+    }
+}
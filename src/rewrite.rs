@@ -0,0 +1,90 @@
+//! In-place rewriting of IDs embedded in text such as URLs, log lines and
+//! SQL dumps.
+
+use anyhow::Result;
+use regex::{Captures, Regex};
+use std::borrow::Cow;
+
+use crate::input::Lines;
+use crate::output::{OutputWriter, Record};
+
+/// A hyphenated UUID not run together with other word characters.
+pub const UUID_PATTERN: &str =
+    r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b";
+
+/// A 22-character run of Base58 characters. Any such word is taken for a
+/// B58UUID, so long alphanumeric words in the text can match too.
+pub const B58UUID_PATTERN: &str = r"\b[1-9A-HJ-NP-Za-km-z]{22}\b";
+
+/// A UUID string literal in SQL: a canonical UUID directly inside single
+/// quotes.
+const SQL_UUID_PATTERN: &str =
+    r"'([0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12})'";
+
+/// A B58UUID string literal in SQL.
+const SQL_B58UUID_PATTERN: &str = r"'([1-9A-HJ-NP-Za-km-z]{22})'";
+
+/// Replace every quoted UUID literal in a SQL dump with the quoted B58UUID
+/// (or, with `decode`, the reverse). The dump is handled as bytes, so
+/// everything but the rewritten literals, encoding and line endings
+/// included, comes out exactly as it went in. Returns the rewritten dump and
+/// the number of literals replaced; literals that fail to convert are kept.
+pub fn sql_rewrite(dump: &[u8], decode: bool) -> Result<(Vec<u8>, usize)> {
+    let pattern = regex::bytes::Regex::new(if decode {
+        SQL_B58UUID_PATTERN
+    } else {
+        SQL_UUID_PATTERN
+    })?;
+    let mut replaced = 0;
+    let rewritten = pattern.replace_all(dump, |caps: &regex::bytes::Captures| {
+        // The pattern only matches ASCII
+        let token = std::str::from_utf8(&caps[1]).unwrap_or_default();
+        let converted = if decode {
+            crate::alphabet::decode_to_uuid(token)
+        } else {
+            crate::alphabet::encode_uuid(token)
+        };
+        match converted {
+            Ok(converted) => {
+                replaced += 1;
+                format!("'{}'", converted).into_bytes()
+            }
+            Err(_) => caps[0].to_vec(),
+        }
+    });
+    let rewritten = match rewritten {
+        Cow::Borrowed(unchanged) => unchanged.to_vec(),
+        Cow::Owned(rewritten) => rewritten,
+    };
+    Ok((rewritten, replaced))
+}
+
+/// Replace every UUID in each line with its B58UUID (or, with `decode`, every
+/// B58UUID with its UUID), leaving the rest of the line untouched. Tokens that
+/// fail to convert are kept as they are.
+pub fn url_rewrite(lines: Lines, decode: bool, out: &mut dyn OutputWriter) -> Result<()> {
+    let pattern = Regex::new(if decode {
+        B58UUID_PATTERN
+    } else {
+        UUID_PATTERN
+    })?;
+    for line in lines {
+        let line = line?;
+        let rewritten = pattern.replace_all(&line, |caps: &Captures| {
+            let token = &caps[0];
+            let converted = if decode {
+                crate::alphabet::decode_to_uuid(token).map(|uuid| crate::output::uuid_case(&uuid))
+            } else {
+                crate::alphabet::encode_uuid(token)
+            };
+            converted.unwrap_or_else(|_| token.to_string())
+        });
+        let rewritten = rewritten.into_owned();
+        out.record(
+            Record::new(rewritten.clone())
+                .field("input", line)
+                .field("output", rewritten),
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,145 @@
+//! `--csv` and `--where COL=VALUE`: convert the ID column of a CSV file, in
+//! every row or (with `--where`) only in rows where another column has a
+//! given value, copying every other cell as is.
+//!
+//! The output is the input CSV itself (header included) rather than one
+//! record per value, so a table, or a subset of one, can be migrated in
+//! place. A value that fails to convert is reported and its row copied
+//! unchanged, or dropped under `--strict`.
+
+use anyhow::{anyhow, Context, Result};
+use colored::*;
+use std::io::{Read, Write};
+
+use crate::input::{self, WhereFilter};
+
+/// Which rows and column a run converts.
+pub struct Rows<'a> {
+    /// Header name or 1-based index of the column; the first when unset.
+    pub column: Option<&'a str>,
+    /// Convert only the rows matching this; every row when unset.
+    pub filter: Option<&'a WhereFilter>,
+    /// Leave out rows whose value fails to convert instead of copying them.
+    pub drop_failed: bool,
+}
+
+/// What happened to the rows of one run.
+#[derive(Default)]
+pub struct RowCounts {
+    pub rows: usize,
+    /// Rows matching `--where`; `None` without it, when every row is
+    /// converted.
+    pub matched: Option<usize>,
+    pub errors: usize,
+}
+
+impl RowCounts {
+    pub fn report(&self) {
+        match self.matched {
+            Some(matched) => eprintln!(
+                "Processed {} rows: {} matched --where, {} converted, {} errors",
+                self.rows,
+                matched,
+                matched - self.errors,
+                self.errors
+            ),
+            None => eprintln!(
+                "Processed {} rows: {} converted, {} errors",
+                self.rows,
+                self.rows - self.errors,
+                self.errors
+            ),
+        }
+    }
+}
+
+/// Index of the column called `name`, or failing that, numbered `name`
+/// from 1.
+fn column(headers: &csv::StringRecord, name: &str) -> Result<usize> {
+    headers
+        .iter()
+        .position(|h| h == name)
+        .or_else(|| {
+            name.parse::<usize>()
+                .ok()
+                .filter(|&index| (1..=headers.len()).contains(&index))
+                .map(|index| index - 1)
+        })
+        .ok_or_else(|| anyhow!("CSV input has no column '{}'", name))
+}
+
+/// Copy the CSV from `input` to `output`, replacing the `rows.column` column
+/// with `convert` of its value in the rows chosen by `rows.filter`.
+pub fn rewrite_rows(
+    input: Box<dyn Read>,
+    output: &mut dyn Write,
+    rows: &Rows,
+    convert: impl Fn(&str) -> Result<String, String>,
+) -> Result<RowCounts> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(input);
+    let headers = reader
+        .headers()
+        .context("Failed to read CSV header")?
+        .clone();
+    let target = match rows.column {
+        Some(name) => column(&headers, name)?,
+        None => 0,
+    };
+    let filter = match rows.filter {
+        Some(filter) => Some((column(&headers, &filter.column)?, filter.value.as_str())),
+        None => None,
+    };
+
+    let mut writer = csv::WriterBuilder::new().flexible(true).from_writer(output);
+    writer.write_record(&headers)?;
+    let mut counts = RowCounts {
+        matched: filter.map(|_| 0),
+        ..RowCounts::default()
+    };
+    for row in reader.records() {
+        let record = row.context("Failed to read CSV row")?;
+        counts.rows += 1;
+        if let Some((key, value)) = filter {
+            if record.get(key) != Some(value) {
+                writer.write_record(&record)?;
+                continue;
+            }
+        }
+        if let Some(matched) = &mut counts.matched {
+            *matched += 1;
+        }
+        let value = input::trim(record.get(target).unwrap_or_default());
+        match convert(value) {
+            Ok(converted) => {
+                let fields = record.iter().enumerate().map(|(i, cell)| {
+                    if i == target {
+                        converted.as_str()
+                    } else {
+                        cell
+                    }
+                });
+                writer.write_record(fields)?;
+            }
+            Err(message) => {
+                counts.errors += 1;
+                let line = format!("row {}", counts.rows);
+                if crate::errors::codes() {
+                    eprintln!("{} {}: {}", "Error:".red().bold(), line, message);
+                } else {
+                    eprintln!(
+                        "{} {}: {} - {}",
+                        "Error:".red().bold(),
+                        line,
+                        value,
+                        message
+                    );
+                }
+                if !rows.drop_failed {
+                    writer.write_record(&record)?;
+                }
+            }
+        }
+    }
+    writer.flush()?;
+    Ok(counts)
+}
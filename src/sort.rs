@@ -0,0 +1,232 @@
+//! `--sort` and `--unique` for batch output, with an external merge sort so
+//! inputs larger than memory can be sorted.
+//!
+//! Records are ordered by their `output` field (the converted value). They
+//! are buffered until `--max-memory` is reached, then the buffer is sorted
+//! and spilled to a temporary file as one sorted run. At the end every run is
+//! merged back in a single pass. Sorting is stable, so records with the same
+//! value keep their input order; `--unique` keeps the first of each.
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::output::{OutputWriter, Record};
+
+/// Distinguishes the runs of several writers in one process.
+static NEXT_RUN: AtomicUsize = AtomicUsize::new(0);
+
+/// Sort key of a record: its converted value, or its text if it has none.
+pub fn key(record: &Record) -> String {
+    match record.get("output") {
+        Some(Value::String(output)) => output.clone(),
+        _ => record.text().to_string(),
+    }
+}
+
+/// A sorted run spilled to disk, one JSON record per line.
+struct Run {
+    path: PathBuf,
+}
+
+impl Drop for Run {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Buffers records and writes them to `inner` in sorted order on `finish`.
+pub struct SortingWriter<'a> {
+    inner: Box<dyn OutputWriter + 'a>,
+    unique: bool,
+    /// Buffer size that triggers a spill, in bytes; `None` keeps everything
+    /// in memory.
+    max_memory: Option<usize>,
+    buffer: Vec<(String, Record)>,
+    buffered: usize,
+    runs: Vec<Run>,
+}
+
+impl<'a> SortingWriter<'a> {
+    pub fn new(
+        inner: Box<dyn OutputWriter + 'a>,
+        unique: bool,
+        max_memory_mb: Option<u64>,
+    ) -> Self {
+        SortingWriter {
+            inner,
+            unique,
+            max_memory: max_memory_mb.map(|mb| (mb as usize).saturating_mul(1024 * 1024)),
+            buffer: Vec::new(),
+            buffered: 0,
+            runs: Vec::new(),
+        }
+    }
+
+    /// Sort the buffer in place, dropping repeated keys under `--unique`.
+    fn sort_buffer(&mut self) {
+        self.buffer.sort_by(|a, b| a.0.cmp(&b.0));
+        if self.unique {
+            self.buffer.dedup_by(|later, earlier| later.0 == earlier.0);
+        }
+    }
+
+    fn spill(&mut self) -> Result<()> {
+        self.sort_buffer();
+        let path = std::env::temp_dir().join(format!(
+            "b58uuid-sort-{}-{}.run",
+            std::process::id(),
+            NEXT_RUN.fetch_add(1, Ordering::Relaxed)
+        ));
+        // Registered first so the file is removed even if writing fails
+        self.runs.push(Run { path: path.clone() });
+        let file =
+            File::create(&path).context(format!("Failed to create file: {}", path.display()))?;
+        let mut out = BufWriter::new(file);
+        for (_, record) in self.buffer.drain(..) {
+            writeln!(out, "{}", record.to_json())?;
+        }
+        out.flush()
+            .context(format!("Failed to write file: {}", path.display()))?;
+        self.buffered = 0;
+        Ok(())
+    }
+
+    /// Merge every spilled run into `inner`, lowest key first; ties go to
+    /// the earlier run, which holds the earlier input.
+    fn merge(&mut self) -> Result<()> {
+        let mut readers = Vec::with_capacity(self.runs.len());
+        for run in &self.runs {
+            let file = File::open(&run.path)
+                .context(format!("Failed to read file: {}", run.path.display()))?;
+            readers.push(BufReader::new(file).lines());
+        }
+        let mut next = |run: usize| -> Result<Option<Record>> {
+            match readers[run].next() {
+                Some(line) => {
+                    let record =
+                        Record::from_json(serde_json::from_str(&line?)?).ok_or_else(|| {
+                            anyhow!("Corrupt sort run: {}", self.runs[run].path.display())
+                        })?;
+                    Ok(Some(record))
+                }
+                None => Ok(None),
+            }
+        };
+
+        let mut heads: Vec<Option<Record>> = Vec::with_capacity(self.runs.len());
+        let mut heap = BinaryHeap::new();
+        for run in 0..self.runs.len() {
+            let head = next(run)?;
+            if let Some(record) = &head {
+                heap.push(Reverse((key(record), run)));
+            }
+            heads.push(head);
+        }
+
+        let mut last: Option<String> = None;
+        while let Some(Reverse((key_value, run))) = heap.pop() {
+            let record = heads[run].take().expect("heap entry without a record");
+            if let Some(record) = next(run)? {
+                heap.push(Reverse((key(&record), run)));
+                heads[run] = Some(record);
+            }
+            if self.unique && last.as_ref() == Some(&key_value) {
+                continue;
+            }
+            self.inner.record(record)?;
+            last = Some(key_value);
+        }
+        Ok(())
+    }
+}
+
+impl OutputWriter for SortingWriter<'_> {
+    fn record(&mut self, record: Record) -> Result<()> {
+        let key = key(&record);
+        self.buffered += key.len() + record.approx_size();
+        self.buffer.push((key, record));
+        if self.max_memory.is_some_and(|max| self.buffered >= max) {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    fn error(&mut self, input: &str, message: &str) -> Result<()> {
+        self.inner.error(input, message)
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if self.runs.is_empty() {
+            self.sort_buffer();
+            for (_, record) in self.buffer.drain(..) {
+                self.inner.record(record)?;
+            }
+        } else {
+            if !self.buffer.is_empty() {
+                self.spill()?;
+            }
+            self.merge()?;
+            self.runs.clear();
+        }
+        self.inner.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::testing::{pair, Collect, PAIRS};
+
+    /// `(output, input)` pairs sorted through a writer that spills after
+    /// every record, with the paths of the runs it spilled.
+    fn sort(pairs: &[(&str, &str)], unique: bool) -> (Vec<String>, Vec<PathBuf>) {
+        let sorted = Collect::default();
+        let mut writer = SortingWriter::new(Box::new(sorted.clone()), unique, None);
+        writer.max_memory = Some(1);
+        for (output, input) in pairs {
+            writer.record(pair(output, input)).unwrap();
+        }
+        let runs = writer.runs.iter().map(|run| run.path.clone()).collect();
+        writer.finish().unwrap();
+        (sorted.inputs(), runs)
+    }
+
+    #[test]
+    fn spilled_runs_merge_in_stable_order() {
+        let (sorted, runs) = sort(&PAIRS, false);
+        assert_eq!(runs.len(), PAIRS.len());
+        assert_eq!(sorted, ["2", "4", "6", "3", "1", "5"]);
+    }
+
+    #[test]
+    fn unique_keeps_the_first_of_each_value_across_runs() {
+        let (sorted, _) = sort(&PAIRS, true);
+        assert_eq!(sorted, ["2", "3", "1"]);
+    }
+
+    #[test]
+    fn runs_are_removed_after_the_merge() {
+        let (_, runs) = sort(&PAIRS, false);
+        assert!(!runs.is_empty());
+        for run in runs {
+            assert!(!run.exists(), "{} left behind", run.display());
+        }
+    }
+
+    #[test]
+    fn runs_are_removed_when_the_writer_is_dropped() {
+        let mut writer = SortingWriter::new(Box::new(Collect::default()), false, None);
+        writer.max_memory = Some(1);
+        writer.record(pair("a", "1")).unwrap();
+        let runs: Vec<PathBuf> = writer.runs.iter().map(|run| run.path.clone()).collect();
+        assert!(runs[0].exists());
+        drop(writer);
+        assert!(!runs[0].exists());
+    }
+}
@@ -0,0 +1,95 @@
+//! Lexicographically sortable keys from time-based UUIDs.
+//!
+//! A B58UUID is always 22 characters and the Base58 alphabet is in ASCII
+//! order, so sorting B58UUIDs as strings sorts the underlying 128-bit
+//! values. A key therefore only needs the UUID's bytes in time order:
+//!
+//! - v7 and v6 already start with their timestamp, most significant bits
+//!   first, and are used unchanged.
+//! - v1 stores the low timestamp bits first; its key is the same UUID
+//!   rewritten in the v6 layout (RFC 9562, section 5.6), so v1 and v6 keys
+//!   sort together by time.
+//!
+//! v7 counts Unix milliseconds while v1/v6 count 100 ns intervals since 1582,
+//! so v7 keys sort among themselves but not against v1/v6 keys. Other
+//! versions have no timestamp and are rejected.
+
+use anyhow::{bail, Result};
+use colored::*;
+
+use crate::errors::render;
+use crate::input::Lines;
+use crate::output::{OutputWriter, Record};
+
+/// The v6 form of a v1 UUID: the 60-bit timestamp moved to the front, most
+/// significant bits first, with clock sequence and node unchanged.
+fn v1_to_v6(uuid: uuid::Uuid) -> uuid::Uuid {
+    let bytes = uuid.as_bytes();
+    let time_low = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as u64;
+    let time_mid = u16::from_be_bytes([bytes[4], bytes[5]]) as u64;
+    let time_high = (u16::from_be_bytes([bytes[6], bytes[7]]) & 0x0fff) as u64;
+    let timestamp = time_high << 48 | time_mid << 32 | time_low;
+
+    let mut key = *bytes;
+    key[0..4].copy_from_slice(&((timestamp >> 28) as u32).to_be_bytes());
+    key[4..6].copy_from_slice(&(((timestamp >> 12) & 0xffff) as u16).to_be_bytes());
+    key[6..8].copy_from_slice(&(0x6000 | (timestamp & 0x0fff) as u16).to_be_bytes());
+    uuid::Uuid::from_bytes(key)
+}
+
+/// The UUID of `value` (a UUID or B58UUID) and its sortable form.
+fn sortable(value: &str) -> Result<(uuid::Uuid, uuid::Uuid)> {
+    let Some((_, uuid, _)) = crate::classify(value) else {
+        bail!(render(
+            "E_INVALID_FORMAT",
+            "Invalid format: expected UUID or B58UUID"
+        ));
+    };
+    let uuid = uuid::Uuid::parse_str(&uuid)?;
+    let key = match uuid.get_version_num() {
+        6 | 7 => uuid,
+        1 => v1_to_v6(uuid),
+        version => bail!(render(
+            &format!("E_NO_TIMESTAMP:{}", version),
+            format!("UUID version {} has no timestamp to sort by", version)
+        )),
+    };
+    Ok((uuid, key))
+}
+
+fn sortkey_record(value: &str, uuid: uuid::Uuid, key: uuid::Uuid) -> Record {
+    let b58 = b58uuid_cli::encode(key);
+    Record::new(b58.green().to_string())
+        .field("input", value)
+        .field("version", uuid.get_version_num())
+        .field("key_uuid", key.to_string())
+        .field("sortkey", b58)
+}
+
+/// Print the sort key of a single value; exits 1 if it has none.
+pub fn sortkey_value(value: &str, out: &mut dyn OutputWriter) -> Result<()> {
+    let value = value.trim();
+    match sortable(value) {
+        Ok((uuid, key)) => out.record(sortkey_record(value, uuid, key)),
+        Err(e) => {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Print the sort key of every line, reporting lines without one as errors.
+pub fn sortkey_lines(lines: Lines, out: &mut dyn OutputWriter) -> Result<()> {
+    for line in lines {
+        let line = line?;
+        let value = line.trim();
+        if value.is_empty() {
+            continue;
+        }
+        match sortable(value) {
+            Ok((uuid, key)) => out.record(sortkey_record(value, uuid, key))?,
+            Err(e) => out.error(value, &e.to_string())?,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,225 @@
+//! `generate --spec`: generation options read from a JSON file, so a job can
+//! be versioned and rerun as an artifact.
+//!
+//! The spec is one JSON object whose keys are the long flag names of
+//! `generate` (`count`, `version`, `rng`, `seed`, `match`, `shard-prefix`,
+//! ...; `_` may stand for `-`). Every key is optional, and a flag given on the
+//! command line wins over the spec. Unknown keys are reported and ignored, so
+//! a spec written for a newer release still runs.
+
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use colored::*;
+use serde_json::{Map, Value};
+use std::fs;
+use std::time::Duration;
+
+use crate::generate::{parse_duration, parse_namespace, RngKind, UuidVersion};
+
+/// Keys a spec may set, by their flag names.
+const KEYS: &[&str] = &[
+    "count",
+    "uuid",
+    "both",
+    "delimiter",
+    "output",
+    "atomic",
+    "shards",
+    "shard-prefix",
+    "rng",
+    "version",
+    "namespace",
+    "name",
+    "seed",
+    "seed-file",
+    "interval",
+    "infinite",
+    "duration",
+    "rate",
+    "warn-duplicates",
+    "match",
+    "max-attempts",
+];
+
+/// Options read from a spec file; `None` where the spec is silent.
+#[derive(Default)]
+pub struct GenerateSpec {
+    pub count: Option<usize>,
+    pub uuid: Option<bool>,
+    pub both: Option<bool>,
+    pub delimiter: Option<String>,
+    pub output: Option<String>,
+    pub atomic: Option<bool>,
+    pub shards: Option<u32>,
+    pub shard_prefix: Option<String>,
+    pub rng: Option<RngKind>,
+    pub version: Option<UuidVersion>,
+    pub namespace: Option<uuid::Uuid>,
+    pub name: Option<String>,
+    pub seed: Option<u64>,
+    pub seed_file: Option<String>,
+    pub interval: Option<u64>,
+    pub infinite: Option<bool>,
+    pub duration: Option<Duration>,
+    pub rate: Option<u32>,
+    pub warn_duplicates: Option<bool>,
+    pub pattern: Option<String>,
+    pub max_attempts: Option<u64>,
+}
+
+/// Read and check the spec at `path`, warning on stderr about unknown keys.
+pub fn read(path: &str) -> Result<GenerateSpec> {
+    let text = fs::read_to_string(path).context(format!("Failed to read spec: {}", path))?;
+    let doc: Value =
+        serde_json::from_str(&text).context(format!("Invalid JSON in spec: {}", path))?;
+    let Value::Object(object) = doc else {
+        bail!("Spec {} must be a JSON object", path);
+    };
+    let mut fields = Map::new();
+    for (key, value) in object {
+        let name = key.replace('_', "-");
+        if KEYS.contains(&name.as_str()) {
+            fields.insert(name, value);
+        } else {
+            eprintln!(
+                "{} unknown key \"{}\" in spec {} ignored (known: {})",
+                "Warning:".yellow().bold(),
+                key,
+                path,
+                KEYS.join(", ")
+            );
+        }
+    }
+    let spec = Fields { path, fields };
+    Ok(GenerateSpec {
+        count: spec.integer("count")?,
+        uuid: spec.boolean("uuid")?,
+        both: spec.boolean("both")?,
+        delimiter: spec.string("delimiter")?,
+        output: spec.string("output")?,
+        atomic: spec.boolean("atomic")?,
+        shards: spec
+            .integer("shards")?
+            .map(|shards: u64| {
+                u32::try_from(shards)
+                    .ok()
+                    .filter(|&shards| shards >= 1)
+                    .context(format!("\"shards\" in spec {} must be at least 1", path))
+            })
+            .transpose()?,
+        shard_prefix: spec.string("shard-prefix")?,
+        rng: spec.choice("rng")?,
+        version: spec.choice("version")?,
+        namespace: spec
+            .string("namespace")?
+            .map(|namespace| {
+                parse_namespace(&namespace).map_err(|reason| {
+                    spec.invalid("namespace", &format!("a namespace ({})", reason))
+                })
+            })
+            .transpose()?,
+        name: spec.string("name")?,
+        seed: spec.integer("seed")?,
+        seed_file: spec.string("seed-file")?,
+        interval: spec.integer("interval")?,
+        infinite: spec.boolean("infinite")?,
+        duration: spec.seconds("duration")?,
+        rate: spec
+            .integer("rate")?
+            .map(|rate: u32| {
+                Some(rate)
+                    .filter(|&rate| rate >= 1)
+                    .context(format!("\"rate\" in spec {} must be at least 1", path))
+            })
+            .transpose()?,
+        warn_duplicates: spec.boolean("warn-duplicates")?,
+        pattern: spec.string("match")?,
+        max_attempts: spec
+            .integer("max-attempts")?
+            .map(|attempts: u64| {
+                Some(attempts)
+                    .filter(|&attempts| attempts >= 1)
+                    .context(format!(
+                        "\"max-attempts\" in spec {} must be at least 1",
+                        path
+                    ))
+            })
+            .transpose()?,
+    })
+}
+
+/// The known keys of a spec, with typed accessors that name the key and file
+/// when a value has the wrong type.
+struct Fields<'a> {
+    path: &'a str,
+    fields: Map<String, Value>,
+}
+
+impl Fields<'_> {
+    fn invalid(&self, key: &str, expected: &str) -> anyhow::Error {
+        anyhow::anyhow!("\"{}\" in spec {} must be {}", key, self.path, expected)
+    }
+
+    fn integer<T: TryFrom<u64>>(&self, key: &str) -> Result<Option<T>> {
+        let Some(value) = self.fields.get(key) else {
+            return Ok(None);
+        };
+        value
+            .as_u64()
+            .and_then(|n| T::try_from(n).ok())
+            .map(Some)
+            .ok_or_else(|| self.invalid(key, "a non-negative integer"))
+    }
+
+    fn seconds(&self, key: &str) -> Result<Option<Duration>> {
+        let Some(value) = self.fields.get(key) else {
+            return Ok(None);
+        };
+        value
+            .as_f64()
+            .and_then(|secs| parse_duration(&secs.to_string()).ok())
+            .map(Some)
+            .ok_or_else(|| self.invalid(key, "a positive number of seconds"))
+    }
+
+    fn boolean(&self, key: &str) -> Result<Option<bool>> {
+        let Some(value) = self.fields.get(key) else {
+            return Ok(None);
+        };
+        value
+            .as_bool()
+            .map(Some)
+            .ok_or_else(|| self.invalid(key, "true or false"))
+    }
+
+    fn string(&self, key: &str) -> Result<Option<String>> {
+        let Some(value) = self.fields.get(key) else {
+            return Ok(None);
+        };
+        value
+            .as_str()
+            .map(|s| Some(s.to_string()))
+            .ok_or_else(|| self.invalid(key, "a string"))
+    }
+
+    /// A value of one of the flag's choices, given as a string or, for
+    /// numeric choices like `version`, a number.
+    fn choice<T: ValueEnum>(&self, key: &str) -> Result<Option<T>> {
+        let Some(value) = self.fields.get(key) else {
+            return Ok(None);
+        };
+        let text = match value {
+            Value::String(s) => s.clone(),
+            Value::Number(n) => n.to_string(),
+            _ => String::new(),
+        };
+        T::from_str(&text, false).map(Some).map_err(|_| {
+            let choices: Vec<String> = T::value_variants()
+                .iter()
+                .filter_map(|v| v.to_possible_value())
+                .map(|v| v.get_name().to_string())
+                .collect();
+            self.invalid(key, &format!("one of {}", choices.join(", ")))
+        })
+    }
+}
@@ -0,0 +1,119 @@
+//! Step-by-step view of Base58 decoding, for learning and debugging.
+
+use anyhow::{bail, Result};
+use colored::*;
+use serde_json::json;
+use std::io::Write;
+
+/// Base58 alphabet (Bitcoin alphabet), as used by the `b58uuid` crate.
+pub const BASE58_ALPHABET: &[u8; 58] =
+    b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// One character of the input and the accumulator after consuming it.
+struct Step {
+    position: usize,
+    ch: char,
+    digit: u8,
+    total: u128,
+}
+
+/// Full decoding trace of a B58UUID.
+struct Trace {
+    input: String,
+    steps: Vec<Step>,
+    bytes: [u8; 16],
+}
+
+/// Decode `value` digit by digit, recording the running total after each
+/// character (`total = total * 58 + digit`).
+fn trace(value: &str) -> Result<Trace> {
+    if value.is_empty() {
+        bail!("Empty Base58 string");
+    }
+    if let Some(message) = crate::non_ascii_message(value) {
+        bail!(message);
+    }
+
+    let mut steps = Vec::with_capacity(value.len());
+    let mut total = 0u128;
+    for (position, ch) in value.chars().enumerate() {
+        let digit = match BASE58_ALPHABET.iter().position(|&c| c as char == ch) {
+            Some(digit) => digit as u8,
+            None => bail!("Invalid character at position {}: {}", position, ch),
+        };
+        total = match total
+            .checked_mul(58)
+            .and_then(|t| t.checked_add(digit as u128))
+        {
+            Some(total) => total,
+            None => bail!(
+                "Arithmetic overflow at position {}: value exceeds maximum UUID value",
+                position
+            ),
+        };
+        steps.push(Step {
+            position,
+            ch,
+            digit,
+            total,
+        });
+    }
+
+    Ok(Trace {
+        input: value.to_string(),
+        steps,
+        bytes: total.to_be_bytes(),
+    })
+}
+
+pub fn trace_value(value: &str, as_json: bool, out: &mut dyn Write) -> Result<()> {
+    let value = value.trim();
+    let trace = match trace(value) {
+        Ok(trace) => trace,
+        Err(e) => {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+    let uuid = uuid::Uuid::from_bytes(trace.bytes);
+    let hex: Vec<String> = trace.bytes.iter().map(|b| format!("{:02x}", b)).collect();
+
+    if as_json {
+        let steps: Vec<_> = trace
+            .steps
+            .iter()
+            .map(|step| {
+                json!({
+                    "position": step.position,
+                    "char": step.ch.to_string(),
+                    "digit": step.digit,
+                    // u128 does not fit in a JSON number without precision loss
+                    "total": step.total.to_string(),
+                })
+            })
+            .collect();
+        let doc = json!({
+            "input": trace.input,
+            "steps": steps,
+            "bytes": hex.concat(),
+            "uuid": uuid.to_string(),
+        });
+        writeln!(out, "{}", serde_json::to_string_pretty(&doc)?)?;
+        out.flush()?;
+        return Ok(());
+    }
+
+    writeln!(out, "Tracing B58UUID: {}", trace.input.cyan())?;
+    writeln!(out, "  {:>3}  {:<4}  {:>5}  total", "pos", "char", "digit")?;
+    for step in &trace.steps {
+        writeln!(
+            out,
+            "  {:>3}  {:<4}  {:>5}  {}",
+            step.position, step.ch, step.digit, step.total
+        )?;
+    }
+    writeln!(out, "  Bytes: {}", hex.join(" ").cyan())?;
+    writeln!(out, "  UUID:  {}", uuid.to_string().green())?;
+    out.flush()?;
+    Ok(())
+}
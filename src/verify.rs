@@ -0,0 +1,127 @@
+//! `verify`: check that every value survives a round trip through the codec,
+//! as an integrity check for CI. A UUID is encoded and the result decoded; a
+//! B58UUID is decoded and the result encoded. Unlike `validate`, which only
+//! checks the format, this catches values that parse but do not come back
+//! as written, such as a B58UUID with its leading '1's trimmed.
+
+use anyhow::Result;
+use colored::*;
+use serde_json::Value;
+
+use crate::input::Lines;
+use crate::output::{OutputWriter, Record};
+
+/// Outcome of one round trip.
+enum RoundTrip {
+    /// The value, its other form, and that form converted back
+    Done {
+        kind: &'static str,
+        via: String,
+        back: String,
+    },
+    Invalid(String),
+}
+
+fn round_trip(value: &str) -> RoundTrip {
+    let done = |kind, via: String, back| RoundTrip::Done { kind, via, back };
+    if let Ok(uuid) = crate::alphabet::decode_to_uuid(value) {
+        return match crate::alphabet::encode_uuid(&uuid) {
+            Ok(back) => done("b58uuid", uuid, back),
+            Err(e) => RoundTrip::Invalid(e.to_string()),
+        };
+    }
+    match crate::alphabet::encode_uuid(value) {
+        Ok(b58) => match crate::alphabet::decode_to_uuid(&b58) {
+            Ok(back) => done("uuid", b58, back),
+            Err(e) => RoundTrip::Invalid(e.to_string()),
+        },
+        Err(_) => RoundTrip::Invalid(crate::invalid_reason(value)),
+    }
+}
+
+/// Whether `back` is `value` again. UUIDs compare without regard to case,
+/// in the form they were written in (hyphenated or bare hex, inside braces
+/// or a URN); B58UUIDs compare exactly.
+fn matches(kind: &str, value: &str, back: &str) -> bool {
+    if kind == "b58uuid" {
+        return value == back;
+    }
+    let value = b58uuid_cli::unwrap_uuid(value);
+    let back = if value.contains('-') {
+        back.to_string()
+    } else {
+        back.replace('-', "")
+    };
+    value.eq_ignore_ascii_case(&back)
+}
+
+fn verify_record(value: &str) -> Record {
+    let record = Record::new(String::new()).field("input", value);
+    match round_trip(value) {
+        RoundTrip::Done { kind, via, back } if matches(kind, value, &back) => record
+            .with_text(format!("{} {}", "OK".green().bold(), value))
+            .field("type", kind)
+            .field("via", via)
+            .field("round_trip", back)
+            .field("ok", true),
+        RoundTrip::Done { kind, via, back } => record
+            .with_text(format!(
+                "{} {} -> {} -> {}",
+                "MISMATCH".red().bold(),
+                value,
+                via,
+                back
+            ))
+            .field("type", kind)
+            .field("via", via)
+            .field("round_trip", back)
+            .field("ok", false)
+            .failure(),
+        RoundTrip::Invalid(reason) => record
+            .with_text(format!("{} {} - {}", "INVALID".red().bold(), value, reason))
+            .field("type", Value::Null)
+            .field("via", Value::Null)
+            .field("round_trip", Value::Null)
+            .field("ok", false)
+            .failure(),
+    }
+}
+
+/// Round-trip every value, writing one record each (only the failures with
+/// `quiet_success`) and the number of failures (mismatched or invalid
+/// values) to stderr, followed under `--stats` by the summary line of the
+/// other batch commands. Returns whether every value round-tripped.
+pub fn verify(
+    lines: Lines,
+    stats: bool,
+    quiet_success: bool,
+    out: &mut dyn OutputWriter,
+) -> Result<bool> {
+    let (mut checked, mut failed) = (0, 0);
+    for line in lines {
+        let line = line?;
+        let value = line.trim();
+        if value.is_empty() {
+            continue;
+        }
+        checked += 1;
+        let record = verify_record(value);
+        if record.is_failure() {
+            failed += 1;
+        } else if quiet_success {
+            continue;
+        }
+        out.record(record)?;
+    }
+    out.flush()?;
+    eprintln!("Verified {} values: {} failed", checked, failed);
+    if stats {
+        eprintln!(
+            "Processed {} lines: {} ok, {} errors",
+            checked,
+            checked - failed,
+            failed
+        );
+    }
+    Ok(failed == 0)
+}
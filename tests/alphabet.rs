@@ -0,0 +1,199 @@
+mod common;
+
+use common::{run, stderr, stdout, UUID};
+
+fn encode_in(alphabet: &str) -> String {
+    let output = run(&["--alphabet", alphabet, "encode", UUID], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+    stdout(&output).trim().to_string()
+}
+
+#[test]
+fn round_trips_within_each_alphabet() {
+    for alphabet in ["bitcoin", "ripple", "flickr"] {
+        let b58 = encode_in(alphabet);
+        let output = run(&["--alphabet", alphabet, "decode", &b58], "");
+        assert_eq!(stdout(&output).trim(), UUID, "{}", alphabet);
+    }
+}
+
+#[test]
+fn random_values_round_trip_in_every_alphabet() {
+    use rand::{Rng, SeedableRng};
+    let mut rng = rand::rngs::StdRng::seed_from_u64(276);
+    // Raw bytes, so most are not RFC 9562 UUIDs and some carry a high
+    // first digit in one alphabet or another.
+    let uuids: Vec<String> = (0..500)
+        .map(|_| uuid::Uuid::from_bytes(rng.gen()).to_string())
+        .collect();
+    let uuids = uuids.join("\n") + "\n";
+    for alphabet in ["bitcoin", "ripple", "flickr"] {
+        let encoded = run(&["--alphabet", alphabet, "encode"], &uuids);
+        assert!(encoded.status.success(), "{}", stderr(&encoded));
+        let decoded = run(&["--alphabet", alphabet, "decode"], &stdout(&encoded));
+        assert_eq!(stderr(&decoded), "", "{}", alphabet);
+        assert_eq!(stdout(&decoded), uuids, "{}", alphabet);
+    }
+}
+
+#[test]
+fn a_value_too_large_for_the_alphabet_names_the_likely_one() {
+    // Encoded in ripple, this is above 2^128 in both other alphabets.
+    let ripple = "YnEeCH1tWMMnyudMahF7xy";
+    let output = run(&["--alphabet", "ripple", "decode", ripple], "");
+    assert_eq!(
+        stdout(&output).trim(),
+        "fba34d2f-19be-4793-8359-fd4fffab9b22"
+    );
+    let output = run(&["--error-format", "code", "decode", ripple], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output).trim(), "Error: E_WRONG_ALPHABET");
+    let output = run(&["--alphabet", "flickr", "decode", ripple], "");
+    assert!(stderr(&output).contains("try --alphabet ripple"));
+}
+
+#[test]
+fn namespace_is_read_in_the_alphabet() {
+    let dns = run(
+        &[
+            "--alphabet",
+            "flickr",
+            "encode",
+            "6ba7b810-9dad-11d1-80b4-00c04fd430c8",
+        ],
+        "",
+    );
+    let dns = stdout(&dns).trim().to_string();
+    let generate = |args: &[&str]| {
+        let output = run(args, "");
+        assert!(output.status.success(), "{}", stderr(&output));
+        stdout(&output)
+    };
+    assert_eq!(
+        generate(&[
+            "--alphabet",
+            "flickr",
+            "gen",
+            "--uuid",
+            "--version",
+            "5",
+            "--namespace",
+            &dns,
+            "--name",
+            "x"
+        ]),
+        generate(&[
+            "gen",
+            "--uuid",
+            "--version",
+            "5",
+            "--namespace",
+            "dns",
+            "--name",
+            "x"
+        ])
+    );
+}
+
+#[test]
+fn checked_and_binary_forms_follow_the_alphabet() {
+    let bitcoin = run(&["encode", "--check", UUID], "");
+    let flickr = run(&["--alphabet", "flickr", "encode", "--check", UUID], "");
+    assert_ne!(stdout(&bitcoin), stdout(&flickr));
+    let flickr_check = stdout(&flickr).trim().to_string();
+    let decoded = run(
+        &[
+            "--alphabet",
+            "flickr",
+            "transcode",
+            "--input-encoding",
+            "base58check",
+            "--output-encoding",
+            "uuid",
+            &flickr_check,
+        ],
+        "",
+    );
+    assert_eq!(stdout(&decoded).trim(), UUID, "{}", stderr(&decoded));
+
+    let pairs = run(
+        &[
+            "--alphabet",
+            "ripple",
+            "--format",
+            "binary-pairs",
+            "encode",
+            UUID,
+        ],
+        "",
+    );
+    assert!(pairs.status.success(), "{}", stderr(&pairs));
+    assert_eq!(
+        &pairs.stdout[..16],
+        uuid::Uuid::parse_str(UUID).unwrap().as_bytes()
+    );
+    assert_eq!(&pairs.stdout[16..], encode_in("ripple").as_bytes());
+}
+
+#[test]
+fn number_reads_and_writes_the_alphabet() {
+    let ripple = encode_in("ripple");
+    let integer = run(&["number", UUID], "");
+    let in_ripple = run(&["--alphabet", "ripple", "number", &ripple], "");
+    assert_eq!(
+        stdout(&in_ripple),
+        stdout(&integer),
+        "{}",
+        stderr(&in_ripple)
+    );
+
+    let parsed = run(&["--alphabet", "ripple", "number", "--parse", "1"], "");
+    assert_eq!(stdout(&parsed).trim(), "rrrrrrrrrrrrrrrrrrrrrp");
+    let base58 = run(
+        &["--alphabet", "ripple", "number", "--base", "58", UUID],
+        "",
+    );
+    assert_eq!(stdout(&base58).trim(), ripple);
+
+    // Too large in Bitcoin's order, so it errors rather than printing an
+    // integer.
+    let output = run(&["number", "YnEeCH1tWMMnyudMahF7xy"], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).is_empty());
+    assert!(
+        stderr(&output).contains("try --alphabet ripple"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn mappings_record_and_check_the_alphabet() {
+    let output = run(
+        &["--alphabet", "ripple", "encode", "--map-format", "json"],
+        &format!("{}\n", UUID),
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    let doc: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(
+        doc["header"]["alphabet"],
+        "rpshnaf39wBUDNEGHJKLM4PQRST7VWXYZ2bcdeCg65jkm8oFqi1tuvAxyz"
+    );
+    let ripple = encode_in("ripple");
+    assert_eq!(doc["entries"][0]["b58uuid"], ripple.as_str());
+
+    let mapping = common::temp_file("ripple-mapping.json", &output.stdout);
+    let mapping = mapping.to_str().unwrap();
+    let output = run(&["reverse-map", mapping, &ripple], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        stderr(&output).contains("uses the ripple alphabet (pass --alphabet ripple)"),
+        "{}",
+        stderr(&output)
+    );
+    let output = run(
+        &["--alphabet", "ripple", "reverse-map", mapping, &ripple],
+        "",
+    );
+    assert_eq!(stdout(&output).trim(), UUID, "{}", stderr(&output));
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use common::{command, run, stderr, temp_dir};
+
+fn entries(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn atomic_generate_publishes_the_complete_file() {
+    let dir = temp_dir("atomic-complete");
+    let path = dir.join("ids.txt");
+    let path = path.to_str().unwrap();
+    let output = run(&["generate", "-n", "1000", "-o", path, "--atomic"], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(fs::read_to_string(path).unwrap().lines().count(), 1000);
+    assert_eq!(entries(&dir), ["ids.txt"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn interrupted_atomic_generate_leaves_the_old_file_alone() {
+    let dir = temp_dir("atomic-interrupted");
+    let path = dir.join("ids.txt");
+    fs::write(&path, "old\n").unwrap();
+    let mut child = command(&[
+        "generate",
+        "-n",
+        "1000000000",
+        "-o",
+        path.to_str().unwrap(),
+        "--atomic",
+        "--yes",
+    ])
+    .spawn()
+    .expect("the binary starts");
+
+    // Interrupt only once the temporary file is being written.
+    let started = Instant::now();
+    while entries(&dir).len() < 2 {
+        assert!(started.elapsed() < Duration::from_secs(10), "no temp file");
+        thread::sleep(Duration::from_millis(10));
+    }
+    let killed = std::process::Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(killed.success());
+
+    let status = child.wait().unwrap();
+    assert_eq!(status.code(), Some(130));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "old\n");
+    assert_eq!(entries(&dir), ["ids.txt"]);
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::Stdio;
+use std::thread;
+
+use common::{command, B58, UUID};
+
+/// Run `args` over `input`, read one line of its output and close the pipe,
+/// as `| head -n 1` would. Returns the line, the exit status and stderr.
+fn read_one_line(args: &[&str], input: String) -> (String, std::process::ExitStatus, String) {
+    let mut child = command(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the binary starts");
+    let mut stdin = child.stdin.take().expect("piped stdin");
+    // The binary stops reading when it stops writing, so this write may
+    // fail; that is not what is under test.
+    let feeder = thread::spawn(move || {
+        let _ = stdin.write_all(input.as_bytes());
+    });
+    let mut errors = child.stderr.take().expect("piped stderr");
+    let errors = thread::spawn(move || {
+        let mut text = String::new();
+        errors.read_to_string(&mut text).unwrap();
+        text
+    });
+
+    let mut stdout = BufReader::new(child.stdout.take().expect("piped stdout"));
+    let mut line = String::new();
+    stdout.read_line(&mut line).unwrap();
+    drop(stdout);
+
+    let status = child.wait().unwrap();
+    feeder.join().unwrap();
+    (line, status, errors.join().unwrap())
+}
+
+#[test]
+fn every_output_loop_exits_quietly_when_the_reader_leaves() {
+    let uuids = format!("{}\n", UUID).repeat(200_000);
+    let b58s = format!("{}\n", B58).repeat(200_000);
+    let cases: [(&[&str], &String, &str); 4] = [
+        (&["generate", "-n", "1000000"], &String::new(), ""),
+        (&["encode"], &uuids, B58),
+        (&["decode"], &b58s, UUID),
+        (
+            &[
+                "transcode",
+                "--input-encoding",
+                "uuid",
+                "--output-encoding",
+                "base32",
+            ],
+            &uuids,
+            "",
+        ),
+    ];
+    for (args, input, expected) in cases {
+        let (line, status, errors) = read_one_line(args, input.clone());
+        assert!(line.contains(expected), "{:?}: {}", args, line);
+        assert!(
+            status.success(),
+            "{:?} exited with {}: {}",
+            args,
+            status,
+            errors
+        );
+        assert!(!errors.contains("panicked"), "{:?}: {}", args, errors);
+        assert!(errors.is_empty(), "{:?}: {}", args, errors);
+    }
+}
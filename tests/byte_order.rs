@@ -0,0 +1,76 @@
+mod common;
+
+use common::{run, stderr, stdout};
+
+/// UUID, network-order and mixed-order B58UUIDs, computed independently with
+/// Python (`int.from_bytes(uuid.UUID(u).bytes, "big")` and `.bytes_le`) and
+/// the Bitcoin alphabet.
+const VECTORS: [(&str, &str, &str); 5] = [
+    (
+        "00000000-0000-0000-0000-000000000000",
+        "1111111111111111111111",
+        "1111111111111111111111",
+    ),
+    (
+        "00112233-4455-6677-8899-aabbccddeeff",
+        "11UoWww8DGaVGLtea7zU7p",
+        "7KDjUdt5Bp1SDdGkp5buGS",
+    ),
+    (
+        "550e8400-e29b-41d4-a716-446655440000",
+        "BWBeN28Vb7cMEx7Ym8AUzs",
+        "14hHMkgQE2ZTqT1xmn3Hwd",
+    ),
+    (
+        "6ba7b810-9dad-11d1-80b4-00c04fd430c8",
+        "EJ34kCVxxF9jHMKD4EgrAK",
+        "34m3mSxKaSU9nG4Ym2s8mu",
+    ),
+    (
+        "ffffffff-ffff-ffff-ffff-ffffffffffff",
+        "YcVfxkQb6JRzqk5kF2tNLv",
+        "YcVfxkQb6JRzqk5kF2tNLv",
+    ),
+];
+
+fn converted(args: &[&str]) -> String {
+    let output = run(args, "");
+    assert!(output.status.success(), "{:?}: {}", args, stderr(&output));
+    stdout(&output).trim().to_string()
+}
+
+#[test]
+fn network_order_matches_the_vectors() {
+    for (uuid, network, _) in VECTORS {
+        assert_eq!(converted(&["encode", uuid]), network);
+        assert_eq!(
+            converted(&["encode", uuid, "--byte-order", "network"]),
+            network
+        );
+        assert_eq!(
+            converted(&["decode", network, "--byte-order", "network"]),
+            uuid
+        );
+    }
+}
+
+#[test]
+fn mixed_order_matches_the_vectors() {
+    for (uuid, _, mixed) in VECTORS {
+        assert_eq!(converted(&["encode", uuid, "--byte-order", "mixed"]), mixed);
+        assert_eq!(converted(&["decode", mixed, "--byte-order", "mixed"]), uuid);
+    }
+}
+
+#[test]
+fn random_uuids_round_trip_in_each_order() {
+    let uuids: Vec<String> = (0..500).map(|_| uuid::Uuid::new_v4().to_string()).collect();
+    let uuids = uuids.join("\n") + "\n";
+    for order in ["network", "mixed"] {
+        let encoded = run(&["encode", "--byte-order", order], &uuids);
+        assert!(encoded.status.success(), "{}", stderr(&encoded));
+        let decoded = run(&["decode", "--byte-order", order], &stdout(&encoded));
+        assert_eq!(stderr(&decoded), "", "{}", order);
+        assert_eq!(stdout(&decoded), uuids, "{}", order);
+    }
+}
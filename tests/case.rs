@@ -0,0 +1,40 @@
+mod common;
+
+use common::{run, stdout, B58, UUID};
+
+#[test]
+fn uppercase_decode_round_trips() {
+    let output = run(&["decode", "--uppercase", B58], "");
+    let upper = stdout(&output).trim().to_string();
+    assert_eq!(upper, UUID.to_uppercase());
+    let back = run(&["encode", &upper], "");
+    assert_eq!(stdout(&back).trim(), B58);
+}
+
+#[test]
+fn lowercase_folds_uuid_input() {
+    let output = run(&["decode", "--lowercase", B58], "");
+    assert_eq!(stdout(&output).trim(), UUID);
+    let output = run(
+        &["validate", "--lowercase"],
+        &format!("{}\n", UUID.to_uppercase()),
+    );
+    assert!(stdout(&output).contains(UUID), "{}", stdout(&output));
+}
+
+#[test]
+fn validate_prints_the_uuid_in_the_chosen_case() {
+    let output = run(&["validate", "--uppercase", UUID], "");
+    assert!(stdout(&output).contains(&format!("UUID:    {}", UUID.to_uppercase())));
+    let output = run(&["validate", "--uppercase"], &format!("{}\n", UUID));
+    assert!(stdout(&output).contains(&UUID.to_uppercase()));
+    let output = run(&["--format", "json", "validate", "--uppercase", B58], "");
+    let record: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(record["uuid"], UUID.to_uppercase());
+}
+
+#[test]
+fn case_flags_exclude_each_other() {
+    let output = run(&["decode", "--uppercase", "--lowercase", B58], "");
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -0,0 +1,27 @@
+mod common;
+
+use common::{command, B58};
+
+fn output(args: &[&str], no_color: Option<&str>) -> String {
+    let mut command = command(args);
+    command.env_remove("NO_COLOR");
+    if let Some(value) = no_color {
+        command.env("NO_COLOR", value);
+    }
+    let output = command.output().expect("the binary runs");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn no_color_keeps_escape_codes_out() {
+    let text = output(&["validate", B58], Some("1"));
+    assert!(!text.contains('\u{1b}'), "{:?}", text);
+}
+
+#[test]
+fn color_always_wins_over_no_color() {
+    let text = output(&["--color", "always", "validate", B58], Some("1"));
+    assert!(text.contains('\u{1b}'), "{:?}", text);
+    let text = output(&["--no-color", "validate", B58], None);
+    assert!(!text.contains('\u{1b}'), "{:?}", text);
+}
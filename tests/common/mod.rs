@@ -0,0 +1,63 @@
+//! Runs the built `b58uuid` binary for the end-to-end tests.
+
+#![allow(dead_code)]
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+pub const UUID: &str = "550e8400-e29b-41d4-a716-446655440000";
+pub const B58: &str = "BWBeN28Vb7cMEx7Ym8AUzs";
+
+/// The binary with `args`, colors off, ready to run.
+pub fn command(args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_b58uuid"));
+    command
+        .args(args)
+        .env("NO_COLOR", "1")
+        .env("RUST_BACKTRACE", "0");
+    command
+}
+
+/// Run the binary with `args` and `stdin` as its input.
+pub fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = command(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the binary starts");
+    // Feed stdin from its own thread: a large input would otherwise fill the
+    // pipe while the binary blocks writing output nobody is reading yet.
+    let mut input = child.stdin.take().expect("piped stdin");
+    let bytes = stdin.as_bytes().to_vec();
+    let feeder = std::thread::spawn(move || input.write_all(&bytes));
+    let output = child.wait_with_output().expect("the binary exits");
+    // The binary may exit without reading everything, so a broken pipe here
+    // is the test's business, not the helper's.
+    let _ = feeder.join().expect("the stdin feeder does not panic");
+    output
+}
+
+pub fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).expect("UTF-8 output")
+}
+
+pub fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).expect("UTF-8 errors")
+}
+
+/// A file named `name` holding `contents`, in a directory of its own under
+/// the system temp dir so parallel tests never share one.
+pub fn temp_file(name: &str, contents: &[u8]) -> std::path::PathBuf {
+    let path = temp_dir(name).join(name);
+    std::fs::write(&path, contents).expect("the temp file is writable");
+    path
+}
+
+/// An empty directory for the test `name`, unique to this process.
+pub fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("b58uuid-test-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("the temp dir is writable");
+    dir
+}
@@ -0,0 +1,46 @@
+mod common;
+
+use common::{run, stderr};
+
+/// The `--error-summary` lines of `command` over `input`, spacing collapsed.
+fn summary(command: &str, input: &str) -> Vec<String> {
+    let output = run(&["-q", command, "--error-summary"], input);
+    assert!(output.status.success(), "{}", stderr(&output));
+    stderr(&output)
+        .lines()
+        .skip_while(|line| *line != "Errors by reason:")
+        .skip(1)
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect()
+}
+
+#[test]
+fn uuid_errors_are_counted_by_kind() {
+    let input = "550e8400-e29b-41d4-a716-44665544000g\n\
+                 550e84-00e29b-41d4-a716-446655440000\n\
+                 550e8400-e29b-41d4-a716-44665544000x\n\
+                 550e8400\n";
+    assert_eq!(
+        summary("encode", input),
+        [
+            "invalid hex digit 2",
+            "misplaced hyphens 1",
+            "invalid length 1"
+        ]
+    );
+}
+
+#[test]
+fn base58_errors_are_counted_by_kind() {
+    let input = "BWBeN28Vb7cMEx7Ym8AUz0\n\
+                 11111111111111111111111\n\
+                 zzzzzzzzzzzzzzzzzzzzzz\n";
+    assert_eq!(
+        summary("decode", input),
+        [
+            "invalid Base58 character 1",
+            "too many leading 1s 1",
+            "overflow 1"
+        ]
+    );
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use std::io::{BufRead, BufReader, Write};
+use std::process::Stdio;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use common::{command, B58, UUID};
+
+/// Lines the child writes to stdout, sent as they are read.
+fn stdout_lines(child: &mut std::process::Child) -> mpsc::Receiver<(Instant, String)> {
+    let stdout = BufReader::new(child.stdout.take().expect("piped stdout"));
+    let (lines, received) = mpsc::channel();
+    thread::spawn(move || {
+        for line in stdout.lines() {
+            if lines.send((Instant::now(), line.unwrap())).is_err() {
+                break;
+            }
+        }
+    });
+    received
+}
+
+const PATIENCE: Duration = Duration::from_secs(10);
+
+#[test]
+fn batch_results_reach_the_pipe_while_stdin_is_still_open() {
+    for args in [
+        &["encode"][..],
+        &["--flush", "--format", "ndjson", "encode"],
+    ] {
+        let mut child = command(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("the binary starts");
+        let mut stdin = child.stdin.take().expect("piped stdin");
+        let lines = stdout_lines(&mut child);
+        // Each reply has to arrive before the next line is sent: a block
+        // buffer would hold it until stdin closes.
+        for _ in 0..3 {
+            writeln!(stdin, "{}", UUID).unwrap();
+            stdin.flush().unwrap();
+            let (_, line) = lines
+                .recv_timeout(PATIENCE)
+                .unwrap_or_else(|_| panic!("{:?}: no reply while stdin is open", args));
+            assert!(line.contains(B58), "{:?}: {}", args, line);
+        }
+        drop(stdin);
+        assert!(child.wait().unwrap().success());
+    }
+}
+
+#[test]
+fn interval_values_are_flushed_as_they_are_drawn() {
+    let mut child = command(&["generate", "--interval", "200", "-n", "3"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("the binary starts");
+    let lines = stdout_lines(&mut child);
+    let arrivals: Vec<Instant> = (0..3)
+        .map(|_| lines.recv_timeout(PATIENCE).expect("a value").0)
+        .collect();
+    assert!(child.wait().unwrap().success());
+    // Buffered until exit, all three would arrive together.
+    assert!(
+        arrivals[2] - arrivals[0] >= Duration::from_millis(300),
+        "{:?}",
+        arrivals[2] - arrivals[0]
+    );
+}
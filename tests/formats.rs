@@ -0,0 +1,69 @@
+mod common;
+
+use common::{run, stderr, stdout, B58, UUID};
+
+/// Fields of the records `args` write as ndjson.
+fn ndjson(args: &[&str]) -> Vec<serde_json::Value> {
+    let args: Vec<&str> = ["--format", "ndjson"].iter().chain(args).copied().collect();
+    let output = run(&args, "");
+    assert!(output.status.success(), "{:?}: {}", args, stderr(&output));
+    stdout(&output)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn every_command_writes_every_format() {
+    let commands: [&[&str]; 4] = [
+        &["encode", UUID],
+        &["decode", B58],
+        &["validate", B58],
+        &["generate", "-n", "2", "--seed", "7"],
+    ];
+    for command in commands {
+        for format in ["text", "json", "ndjson", "csv", "table"] {
+            let args: Vec<&str> = ["--format", format]
+                .iter()
+                .chain(command)
+                .copied()
+                .collect();
+            let output = run(&args, "");
+            assert!(output.status.success(), "{:?}: {}", args, stderr(&output));
+            assert!(!output.stdout.is_empty(), "{:?}", args);
+        }
+    }
+}
+
+#[test]
+fn formats_agree_on_the_fields() {
+    let records = ndjson(&["encode", UUID]);
+    assert_eq!(records.len(), 1);
+    let names: Vec<&String> = records[0].as_object().unwrap().keys().collect();
+
+    let csv = stdout(&run(&["--format", "csv", "encode", UUID], ""));
+    let header: Vec<&str> = csv.lines().next().unwrap().split(',').collect();
+    assert_eq!(names, header);
+
+    let json = stdout(&run(&["--format", "json", "encode", UUID], ""));
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(json, records[0]);
+    assert_eq!(json["output"], B58);
+
+    let table = stdout(&run(&["--format", "table", "encode", UUID], ""));
+    let header: Vec<String> = names.iter().map(|name| name.to_uppercase()).collect();
+    assert_eq!(
+        table
+            .lines()
+            .next()
+            .unwrap()
+            .split_whitespace()
+            .collect::<Vec<_>>(),
+        header
+    );
+}
+
+#[test]
+fn generate_writes_one_record_per_value() {
+    assert_eq!(ndjson(&["generate", "-n", "3", "--seed", "7"]).len(), 3);
+}
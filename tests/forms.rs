@@ -0,0 +1,23 @@
+mod common;
+
+use common::{run, stderr, stdout, B58};
+
+#[test]
+fn encode_reads_every_canonical_variant() {
+    let input = "{550e8400-e29b-41d4-a716-446655440000}\n\
+                 urn:uuid:550e8400-e29b-41d4-a716-446655440000\n\
+                 550e8400e29b41d4a716446655440000\n";
+    let output = run(&["encode"], input);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), format!("{}\n{}\n{}\n", B58, B58, B58));
+}
+
+#[test]
+fn misplaced_hyphens_fail_unless_tolerated() {
+    let mangled = "550e84-00e29b-41d4-a716-446655440000";
+    let output = run(&["--error-format", "code", "encode", mangled], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output).trim(), "Error: E_MISPLACED_HYPHENS");
+    let output = run(&["encode", "--tolerant-hyphens", mangled], "");
+    assert_eq!(stdout(&output).trim(), B58);
+}
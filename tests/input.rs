@@ -0,0 +1,124 @@
+mod common;
+
+use common::{run, stdout, temp_file, B58, UUID};
+
+const OTHER_UUID: &str = "6ba7b810-9dad-11d1-80b4-00c04fd430c8";
+const OTHER_B58: &str = "EJ34kCVxxF9jHMKD4EgrAK";
+const NIL_UUID: &str = "00000000-0000-0000-0000-000000000000";
+const NIL_B58: &str = "1111111111111111111111";
+
+/// A command, how it is given one value on the command line, and a
+/// different value for each of the three sources.
+struct Case {
+    base: &'static [&'static str],
+    value_flag: Option<&'static str>,
+    value: &'static str,
+    in_file: &'static str,
+    piped: &'static str,
+}
+
+impl Case {
+    fn with_value(&self, value: &str, rest: &[&str]) -> Vec<String> {
+        let mut args: Vec<String> = self.base.iter().map(|arg| arg.to_string()).collect();
+        args.extend(self.value_flag.map(String::from));
+        args.push(value.to_string());
+        args.extend(rest.iter().map(|arg| arg.to_string()));
+        args
+    }
+}
+
+fn output(args: &[String], stdin: &str) -> String {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    stdout(&run(&args, stdin))
+}
+
+#[test]
+fn every_command_prefers_value_then_file_then_stdin() {
+    let cases = [
+        Case {
+            base: &["encode"],
+            value_flag: None,
+            value: OTHER_UUID,
+            in_file: UUID,
+            piped: NIL_UUID,
+        },
+        Case {
+            base: &["decode"],
+            value_flag: None,
+            value: OTHER_B58,
+            in_file: B58,
+            piped: NIL_B58,
+        },
+        Case {
+            base: &["validate"],
+            value_flag: None,
+            value: OTHER_B58,
+            in_file: B58,
+            piped: NIL_B58,
+        },
+        Case {
+            base: &["generate", "--version", "5", "--namespace", "dns"],
+            value_flag: Some("--name"),
+            value: "example.org",
+            in_file: "www.example.com",
+            piped: "example.net",
+        },
+    ];
+    for case in cases {
+        let command = case.base[0];
+        let file = temp_file(
+            &format!("{}.txt", command),
+            format!("{}\n", case.in_file).as_bytes(),
+        );
+        let file = file.to_str().unwrap();
+        let piped = format!("{}\n", case.piped);
+        let base: Vec<String> = case.base.iter().map(|arg| arg.to_string()).collect();
+        let mut with_file = base.clone();
+        with_file.extend(["--file".to_string(), file.to_string()]);
+
+        // A batch prints differently from a single value, so each source is
+        // compared with the same value given the same way.
+        let from_value = output(&case.with_value(case.value, &[]), "");
+        let from_file = output(&base, &format!("{}\n", case.in_file));
+        let from_stdin = output(&base, &piped);
+        assert_ne!(
+            from_file, from_stdin,
+            "{}: the sources must differ",
+            command
+        );
+        assert_eq!(
+            output(&case.with_value(case.value, &["--file", file]), &piped),
+            from_value,
+            "{}: a value wins over --file and stdin",
+            command
+        );
+        assert_eq!(
+            output(&with_file, &piped),
+            from_file,
+            "{}: --file wins over stdin",
+            command
+        );
+        assert!(
+            !from_stdin.is_empty(),
+            "{}: stdin is read without the others",
+            command
+        );
+    }
+}
+
+#[test]
+fn files_stream_every_line_including_an_unterminated_last_one() {
+    let uuids = format!("{}\r\n{}\n\n{}", UUID, OTHER_UUID, UUID);
+    let file = temp_file("unterminated.txt", uuids.as_bytes());
+    let output = run(&["encode", "--file", file.to_str().unwrap()], "");
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        format!("{}\n{}\n{}\n", B58, OTHER_B58, B58)
+    );
+
+    let b58s = format!("{}\n{}", B58, OTHER_B58);
+    let file = temp_file("unterminated.b58", b58s.as_bytes());
+    let output = run(&["decode", "--file", file.to_str().unwrap()], "");
+    assert_eq!(stdout(&output), format!("{}\n{}\n", UUID, OTHER_UUID));
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use common::{run, stderr, stdout};
+
+/// Lines each worker converts per batch in the binary.
+const JOB_CHUNK_LINES: usize = 4096;
+
+/// `lines` UUIDs with an invalid value every 997 lines and a blank line
+/// every 1009, so both land on either side of the chunk boundaries.
+fn input(lines: usize) -> String {
+    (0..lines)
+        .map(|i| match i {
+            _ if i % 997 == 3 => format!("not-a-uuid-{}\n", i),
+            _ if i % 1009 == 5 => "\n".to_string(),
+            _ => format!("{:08x}-0000-4000-8000-{:012x}\n", i, i * 7919),
+        })
+        .collect()
+}
+
+fn converted(args: &[&str], input: &str) -> (String, String) {
+    let output = run(args, input);
+    (stdout(&output), stderr(&output))
+}
+
+#[test]
+fn parallel_output_matches_sequential_across_chunks() {
+    let input = input(JOB_CHUNK_LINES * 8 + 123);
+    for command in ["encode", "transcode"] {
+        let sequential = converted(&["--format", "ndjson", command], &input);
+        assert!(sequential.0.contains("\"error\""));
+        for jobs in ["2", "3", "8"] {
+            let parallel = converted(&["--format", "ndjson", command, "--jobs", jobs], &input);
+            assert_eq!(parallel, sequential, "{} --jobs {}", command, jobs);
+        }
+    }
+}
+
+#[test]
+fn text_errors_keep_their_order() {
+    let input = input(JOB_CHUNK_LINES * 2 + 17);
+    let sequential = converted(&["encode"], &input);
+    assert_eq!(converted(&["encode", "--jobs", "2"], &input), sequential);
+    assert_eq!(converted(&["encode", "--jobs", "8"], &input), sequential);
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use common::{run, stderr, stdout};
+
+const NIL: &str = "00000000-0000-0000-0000-000000000000";
+const LEADING_ZERO: &str = "00ff0000-0000-0000-0000-000000000000";
+
+#[test]
+fn encode_pads_leading_zero_bytes_to_22_characters() {
+    let output = run(&["encode"], &format!("{}\n{}\n", NIL, LEADING_ZERO));
+    assert!(output.status.success(), "{}", stderr(&output));
+    let encoded = stdout(&output);
+    let lines: Vec<&str> = encoded.lines().collect();
+    assert_eq!(lines, ["1111111111111111111111", "188n9YPzi1DJSS6unA2P7u"]);
+    assert!(lines.iter().all(|line| line.len() == 22));
+}
+
+#[test]
+fn decode_accepts_padded_input() {
+    let output = run(
+        &["decode"],
+        "1111111111111111111111\n188n9YPzi1DJSS6unA2P7u\n",
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), format!("{}\n{}\n", NIL, LEADING_ZERO));
+}
+
+#[test]
+fn decode_rejects_more_padding_than_22_characters() {
+    let output = run(&["decode", "11111111111111111111111"], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).is_empty());
+    assert!(
+        stderr(&output).contains("Too many leading '1' characters"),
+        "{}",
+        stderr(&output)
+    );
+}
@@ -0,0 +1,19 @@
+mod common;
+
+use common::{run, stderr, stdout, B58, UUID};
+
+#[test]
+fn nul_separated_values_round_trip() {
+    let input = format!("{}\0{}\0", B58, B58);
+    let output = run(&["-0", "decode"], &input);
+    assert_eq!(stdout(&output), format!("{}\0{}\0", UUID, UUID));
+}
+
+#[test]
+fn documents_cannot_be_nul_separated() {
+    for format in ["json", "table"] {
+        let output = run(&["-0", "--format", format, "encode", UUID], "");
+        assert_eq!(output.status.code(), Some(1));
+        assert!(stderr(&output).contains("--null"), "{}", stderr(&output));
+    }
+}
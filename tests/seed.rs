@@ -0,0 +1,64 @@
+mod common;
+
+use common::{run, stderr, stdout, temp_dir};
+
+fn generate(args: &[&str]) -> String {
+    let mut all = vec!["generate", "-n", "50"];
+    all.extend_from_slice(args);
+    let output = run(&all, "");
+    assert!(output.status.success(), "{}", stderr(&output));
+    stdout(&output)
+}
+
+#[test]
+fn the_same_seed_gives_the_same_sequence() {
+    let first = generate(&["--seed", "42"]);
+    assert_eq!(first.lines().count(), 50);
+    assert_eq!(first, generate(&["--seed", "42"]));
+    // Pinned so a change of generator or byte layout shows up here rather
+    // than in someone's fixtures.
+    assert_eq!(first.lines().next(), Some("M43Qg1YxYNzWHdy1vEbNnv"));
+}
+
+#[test]
+fn different_seeds_give_different_sequences() {
+    let first = generate(&["--seed", "42"]);
+    let second = generate(&["--seed", "43"]);
+    assert!(first.lines().all(|line| !second.contains(line)));
+}
+
+#[test]
+fn seeded_values_are_v4_uuids() {
+    for line in generate(&["--seed", "7", "--uuid"]).lines() {
+        let uuid = uuid::Uuid::parse_str(line).unwrap();
+        assert_eq!(uuid.get_version_num(), 4, "{}", line);
+        assert_eq!(uuid.get_variant(), uuid::Variant::RFC4122, "{}", line);
+    }
+}
+
+#[test]
+fn seeded_shards_are_reproducible_and_distinct() {
+    let shards = |name: &str| {
+        let dir = temp_dir(name);
+        let prefix = dir.join("part_");
+        let prefix = prefix.to_str().unwrap();
+        generate(&["--seed", "42", "--shards", "3", "--shard-prefix", prefix]);
+        (0..3)
+            .map(|i| std::fs::read_to_string(dir.join(format!("part_{}.txt", i))).unwrap())
+            .collect::<Vec<_>>()
+    };
+    let first = shards("seed-shards-a");
+    assert_eq!(first, shards("seed-shards-b"));
+    assert_eq!(
+        first
+            .iter()
+            .map(|shard| shard.lines().count())
+            .sum::<usize>(),
+        50
+    );
+
+    let mut seen = std::collections::HashSet::new();
+    for line in first.iter().flat_map(|shard| shard.lines()) {
+        assert!(seen.insert(line), "{} repeats across shards", line);
+    }
+}
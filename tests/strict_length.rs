@@ -0,0 +1,38 @@
+mod common;
+
+use common::{run, stderr, stdout};
+
+/// `00ff0000-…` stored without its one leading '1'.
+const LEGACY: &str = "88n9YPzi1DJSS6unA2P7u";
+const PADDED: &str = "188n9YPzi1DJSS6unA2P7u";
+const UUID: &str = "00ff0000-0000-0000-0000-000000000000";
+
+#[test]
+fn short_legacy_values_decode_by_default() {
+    let output = run(&["decode", LEGACY], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), format!("{}\n", UUID));
+
+    let output = run(&["decode"], &format!("{}\n{}\n", LEGACY, PADDED));
+    assert_eq!(stdout(&output), format!("{}\n{}\n", UUID, UUID));
+}
+
+#[test]
+fn strict_length_rejects_short_values() {
+    let output = run(&["decode", "--strict-length", LEGACY], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).is_empty());
+    assert!(
+        stderr(&output).contains("expected 22, got 21"),
+        "{}",
+        stderr(&output)
+    );
+
+    // In a batch the short value fails alone; the padded one still decodes.
+    let output = run(
+        &["decode", "--strict-length"],
+        &format!("{}\n{}\n", LEGACY, PADDED),
+    );
+    assert_eq!(stdout(&output), format!("{}\n", UUID));
+    assert!(stderr(&output).contains(LEGACY), "{}", stderr(&output));
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::{run, stderr, stdout, B58, UUID};
+
+const DNS: &str = "6ba7b810-9dad-11d1-80b4-00c04fd430c8";
+const DNS_B58: &str = "EJ34kCVxxF9jHMKD4EgrAK";
+
+#[test]
+fn unique_collapses_format_variants_of_one_uuid() {
+    let input = [
+        UUID,
+        DNS,
+        "{550E8400-E29B-41D4-A716-446655440000}",
+        "550e8400e29b41d4a716446655440000",
+        "urn:uuid:550e8400-e29b-41d4-a716-446655440000",
+        DNS,
+    ]
+    .join("\n");
+    let output = run(&["encode", "--unique"], &input);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), format!("{}\n{}\n", B58, DNS_B58));
+}
+
+#[test]
+fn unique_keeps_the_first_input_of_each_result() {
+    let input = ["{550E8400-E29B-41D4-A716-446655440000}", DNS, UUID].join("\n");
+    let output = run(&["--format", "ndjson", "encode", "-u"], &input);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let inputs: Vec<String> = stdout(&output)
+        .lines()
+        .map(|line| {
+            let record: serde_json::Value = serde_json::from_str(line).unwrap();
+            record["input"].as_str().unwrap().to_string()
+        })
+        .collect();
+    assert_eq!(inputs, ["{550E8400-E29B-41D4-A716-446655440000}", DNS]);
+}
+
+#[test]
+fn unique_applies_to_decode_and_transcode() {
+    let input = format!("{}\n{}\n{}\n", B58, DNS_B58, B58);
+    let output = run(&["decode", "--unique"], &input);
+    assert_eq!(stdout(&output), format!("{}\n{}\n", UUID, DNS));
+
+    let output = run(
+        &[
+            "transcode",
+            "--input-encoding",
+            "base58",
+            "--output-encoding",
+            "uuid",
+            "--unique",
+        ],
+        &input,
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), format!("{}\n{}\n", UUID, DNS));
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use common::{run, stderr, stdout};
+
+const DNS_EXAMPLE: &str = "2ed6657d-e927-568b-95e1-2665a8aea6a2";
+
+fn generate_v5(extra: &[&str]) -> std::process::Output {
+    let mut args = vec![
+        "generate",
+        "--version",
+        "5",
+        "--namespace",
+        "dns",
+        "--name",
+        "www.example.com",
+    ];
+    args.extend_from_slice(extra);
+    run(&args, "")
+}
+
+#[test]
+fn dns_namespace_matches_the_rfc_vector() {
+    let output = generate_v5(&["--uuid"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), format!("{}\n", DNS_EXAMPLE));
+
+    let output = generate_v5(&[]);
+    let b58 = stdout(&output);
+    assert_eq!(
+        b58uuid_cli::decode(b58.trim()).unwrap().to_string(),
+        DNS_EXAMPLE
+    );
+}
+
+#[test]
+fn same_namespace_and_name_give_the_same_output() {
+    let first = generate_v5(&[]);
+    let second = generate_v5(&[]);
+    assert!(first.status.success(), "{}", stderr(&first));
+    assert_eq!(stdout(&first), stdout(&second));
+
+    // The namespace given as a UUID is the same namespace.
+    let explicit = run(
+        &[
+            "generate",
+            "--version",
+            "5",
+            "--namespace",
+            "6ba7b810-9dad-11d1-80b4-00c04fd430c8",
+            "--name",
+            "www.example.com",
+        ],
+        "",
+    );
+    assert_eq!(stdout(&explicit), stdout(&first));
+}
+
+#[test]
+fn version_5_without_a_name_is_an_error() {
+    let output = run(&["generate", "--version", "5", "--namespace", "dns"], "");
+    assert!(!output.status.success());
+    assert!(stdout(&output).is_empty());
+    assert!(
+        stderr(&output).contains("--version 5 needs --name"),
+        "{}",
+        stderr(&output)
+    );
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use common::{run, stderr, stdout};
+
+/// `(uuid, b58uuid)` of `count` v7 values from one run.
+fn generate_v7(count: usize) -> Vec<(String, String)> {
+    let count = count.to_string();
+    let output = run(
+        &[
+            "--format",
+            "ndjson",
+            "generate",
+            "-n",
+            &count,
+            "--version",
+            "7",
+        ],
+        "",
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    stdout(&output)
+        .lines()
+        .map(|line| {
+            let record: serde_json::Value = serde_json::from_str(line).unwrap();
+            let field = |name: &str| record[name].as_str().unwrap().to_string();
+            (field("uuid"), field("b58uuid"))
+        })
+        .collect()
+}
+
+#[test]
+fn values_increase_strictly_within_a_run() {
+    let values = generate_v7(5000);
+    assert_eq!(values.len(), 5000);
+    for pair in values.windows(2) {
+        assert!(pair[0].0 < pair[1].0, "{} !< {}", pair[0].0, pair[1].0);
+        // The Bitcoin digits are in ASCII order, so the B58UUIDs sort alike
+        assert!(pair[0].1 < pair[1].1, "{} !< {}", pair[0].1, pair[1].1);
+    }
+}
+
+#[test]
+fn values_are_v7_and_round_trip() {
+    for (uuid, b58) in generate_v7(100) {
+        let parsed = uuid::Uuid::parse_str(&uuid).unwrap();
+        assert_eq!(parsed.get_version_num(), 7);
+        assert_eq!(b58uuid_cli::decode_to_uuid(&b58).unwrap(), uuid);
+        assert_eq!(b58uuid_cli::encode_uuid(&uuid).unwrap(), b58);
+    }
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use common::{run, stderr, stdout, B58, UUID};
+
+#[test]
+fn short_and_misplaced_values_are_invalid() {
+    for value in [
+        "2",
+        "bad",
+        "1BWBeN28Vb7cMEx7Ym8AUzs",
+        "550e84-00e29b-41d4-a716-446655440000",
+    ] {
+        let output = run(&["validate", value], "");
+        assert_eq!(output.status.code(), Some(1), "{}", value);
+    }
+    for value in [B58, UUID] {
+        assert!(run(&["validate", value], "").status.success(), "{}", value);
+    }
+}
+
+#[test]
+fn batch_fails_when_any_line_is_invalid() {
+    let output = run(&["validate"], &format!("{}\n2\n{}\n", B58, UUID));
+    assert_eq!(output.status.code(), Some(1));
+    // Failures go to stderr, successes stay on stdout
+    assert_eq!(stdout(&output).lines().count(), 2);
+    assert!(stderr(&output).contains("2 - Invalid format"));
+}
+
+#[test]
+fn stream_reports_every_line_with_its_reason() {
+    let output = run(
+        &["validate", "--stream"],
+        &format!("{}\n2\n\n1{}\n{}", B58, B58, UUID),
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let results: Vec<serde_json::Value> = stdout(&output)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let valid: Vec<bool> = results
+        .iter()
+        .map(|r| r["valid"].as_bool().unwrap())
+        .collect();
+    assert_eq!(valid, [true, false, false, false, true]);
+    assert_eq!(results[1]["reason"], "Invalid length: expected 22, got 1");
+    assert_eq!(results[2]["reason"], "Empty line");
+    assert_eq!(results[3]["reason"], "Invalid length: expected 22, got 23");
+    assert_eq!(results[4]["type"], "uuid");
+}
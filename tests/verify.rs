@@ -0,0 +1,44 @@
+mod common;
+
+use common::{run, stderr, stdout, B58, UUID};
+
+/// Two values that round-trip, a trimmed B58UUID that does not, and a
+/// value that is not an ID at all.
+fn input() -> String {
+    format!("{}\n{}\n{}\nzz0\n", B58, &B58[1..], UUID)
+}
+
+#[test]
+fn quiet_success_prints_only_failures() {
+    let output = run(
+        &["--format", "ndjson", "verify", "--quiet-success"],
+        &input(),
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let inputs: Vec<String> = stdout(&output)
+        .lines()
+        .map(|line| {
+            let record: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(record["ok"], false, "{}", line);
+            record["input"].as_str().unwrap().to_string()
+        })
+        .collect();
+    assert_eq!(inputs, [&B58[1..], "zz0"]);
+}
+
+#[test]
+fn quiet_success_keeps_the_exit_code_and_the_tally() {
+    let loud = run(&["verify", "--stats"], &input());
+    let quiet = run(&["verify", "--quiet-success", "--stats"], &input());
+    assert_eq!(quiet.status.code(), loud.status.code());
+    assert!(stderr(&quiet).contains("Verified 4 values: 2 failed"));
+    assert!(stderr(&quiet).contains("Processed 4 lines: 2 ok, 2 errors"));
+    assert!(!stdout(&quiet).contains("OK"), "{}", stdout(&quiet));
+
+    let passing = run(
+        &["verify", "--quiet-success"],
+        &format!("{}\n{}\n", B58, UUID),
+    );
+    assert!(passing.status.success(), "{}", stderr(&passing));
+    assert_eq!(stdout(&passing), "");
+}
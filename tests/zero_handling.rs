@@ -0,0 +1,55 @@
+mod common;
+
+use common::{run, stderr, stdout};
+
+/// UUIDs with 0, 1, 3 and 16 leading zero bytes, and their padded B58UUIDs.
+const HIGH_ZEROS: [(&str, &str); 4] = [
+    (
+        "ff0000ff-0000-4000-8000-000000000000",
+        "YVLH4pPKQYj8jdZ8Z4Nxzb",
+    ),
+    (
+        "00ffffff-ffff-4fff-bfff-ffffffffffff",
+        "18AQGAuswUdcWNZUsDHpAS",
+    ),
+    (
+        "000000ff-0000-4000-8000-000000000000",
+        "1111NEtcRcSAUqUBFEHLZ5",
+    ),
+    (
+        "00000000-0000-0000-0000-000000000000",
+        "1111111111111111111111",
+    ),
+];
+
+fn converted(args: &[&str]) -> String {
+    let output = run(args, "");
+    assert!(output.status.success(), "{:?}: {}", args, stderr(&output));
+    stdout(&output).trim().to_string()
+}
+
+#[test]
+fn pad_always_writes_22_characters() {
+    for (uuid, b58) in HIGH_ZEROS {
+        assert_eq!(converted(&["encode", uuid]), b58);
+        assert_eq!(converted(&["encode", uuid, "--zero-handling", "pad"]), b58);
+    }
+}
+
+#[test]
+fn trim_drops_leading_zero_digits_but_keeps_one() {
+    for (uuid, b58) in HIGH_ZEROS {
+        let trimmed = converted(&["encode", uuid, "--zero-handling", "trim"]);
+        let expected = b58.trim_start_matches('1');
+        assert_eq!(trimmed, if expected.is_empty() { "1" } else { expected });
+    }
+}
+
+#[test]
+fn decode_reads_both_forms() {
+    for (uuid, b58) in HIGH_ZEROS {
+        let trimmed = converted(&["encode", uuid, "--zero-handling", "trim"]);
+        assert_eq!(converted(&["decode", b58]), uuid);
+        assert_eq!(converted(&["decode", &trimmed]), uuid);
+    }
+}